@@ -0,0 +1,96 @@
+use std::hint::black_box;
+
+use cloudr::DataCloud;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use fxhash::FxHashMap;
+
+const SIZES: [usize; 3] = [16, 1_024, 65_536];
+
+fn keys(size: usize) -> Vec<String> {
+    (0..size).map(|i| format!("key-{i}")).collect()
+}
+
+fn get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get");
+    for size in SIZES {
+        let keys = keys(size);
+        let values: Vec<usize> = (0..size).collect();
+
+        let cloud: DataCloud<'_, String, usize> = DataCloud::new();
+        let mut map: FxHashMap<String, &usize> = FxHashMap::default();
+        for (key, value) in keys.iter().zip(values.iter()) {
+            cloud.insert(key.clone(), value);
+            map.insert(key.clone(), value);
+        }
+
+        group.bench_with_input(BenchmarkId::new("DataCloud", size), &keys, |b, keys| {
+            b.iter(|| {
+                for key in keys {
+                    black_box(cloud.get(key));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("FxHashMap", size), &keys, |b, keys| {
+            b.iter(|| {
+                for key in keys {
+                    black_box(map.get(key));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn get_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_all");
+    for size in SIZES {
+        let keys = keys(size);
+        let values: Vec<usize> = (0..size).collect();
+
+        let cloud: DataCloud<'_, String, usize> = DataCloud::new();
+        let mut map: FxHashMap<String, &usize> = FxHashMap::default();
+        for (key, value) in keys.iter().zip(values.iter()) {
+            cloud.insert(key.clone(), value);
+            map.insert(key.clone(), value);
+        }
+
+        group.bench_with_input(BenchmarkId::new("DataCloud", size), &keys, |b, keys| {
+            b.iter(|| black_box(cloud.get_all(keys.iter().collect())))
+        });
+        group.bench_with_input(BenchmarkId::new("FxHashMap", size), &keys, |b, keys| {
+            b.iter(|| black_box(keys.iter().map(|key| map.get(key).copied()).collect::<Vec<_>>()))
+        });
+    }
+    group.finish();
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    for size in SIZES {
+        let keys = keys(size);
+        let values: Vec<usize> = (0..size).collect();
+
+        group.bench_with_input(BenchmarkId::new("DataCloud", size), &keys, |b, keys| {
+            b.iter(|| {
+                let cloud: DataCloud<'_, String, usize> = DataCloud::new();
+                for (key, value) in keys.iter().zip(values.iter()) {
+                    cloud.insert(key.clone(), value);
+                }
+                black_box(cloud)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("FxHashMap", size), &keys, |b, keys| {
+            b.iter(|| {
+                let mut map: FxHashMap<String, &usize> = FxHashMap::default();
+                for (key, value) in keys.iter().zip(values.iter()) {
+                    map.insert(key.clone(), value);
+                }
+                black_box(map)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, get, get_all, insert);
+criterion_main!(benches);
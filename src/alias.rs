@@ -0,0 +1,111 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Write}
+};
+
+/// Decides which key of an alias group survives [`DataCloud::dedup_aliases`](crate::DataCloud::dedup_aliases).
+/// 
+/// An alias group is a set of at least two keys whose references point to the same address. The
+/// policy receives the keys of one group, in arbitrary order, and returns the position of the key
+/// to keep, or `None` to remove the whole group.
+/// 
+/// Besides [`KeepSmallest`], [`KeepLargest`] and [`RemoveAll`], any `FnMut(&[K]) -> Option<usize>`
+/// function is a policy.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::DataCloud;
+/// 
+/// let x = 1;
+/// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+/// cloud.insert("long name", &x);
+/// cloud.insert("short", &x);
+/// 
+/// let shortest = |keys: &[&str]| (0..keys.len()).min_by_key(|&i| keys[i].len());
+/// assert_eq!(vec!["long name"], cloud.dedup_aliases(shortest));
+/// ```
+pub trait DedupPolicy<K> {
+    /// Returns the position in `keys` of the key to keep, or `None` to keep none of them.
+    fn keep(&mut self, keys: &[K]) -> Option<usize>;
+}
+
+/// Keeps the smallest key of every alias group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepSmallest;
+
+/// Keeps the largest key of every alias group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepLargest;
+
+/// Removes every key of every alias group, keeping only the references held by a single key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemoveAll;
+
+impl<K: Ord> DedupPolicy<K> for KeepSmallest {
+    fn keep(&mut self, keys: &[K]) -> Option<usize> {
+        return (0..keys.len()).min_by_key(|&i| &keys[i]);
+    }
+}
+
+impl<K: Ord> DedupPolicy<K> for KeepLargest {
+    fn keep(&mut self, keys: &[K]) -> Option<usize> {
+        return (0..keys.len()).max_by_key(|&i| &keys[i]);
+    }
+}
+
+impl<K> DedupPolicy<K> for RemoveAll {
+    fn keep(&mut self, _keys: &[K]) -> Option<usize> {
+        return None;
+    }
+}
+
+impl<K, F: FnMut(&[K]) -> Option<usize>> DedupPolicy<K> for F {
+    fn keep(&mut self, keys: &[K]) -> Option<usize> {
+        return self(keys);
+    }
+}
+
+/// Returns the address `value` points to along with the size of what it points to, so slices
+/// sharing a start but not a length tell apart. The vtable of a trait object is left out.
+pub(crate) fn referent<V: ?Sized>(value: &V) -> (*const (), usize) {
+    return (value as *const V as *const (), std::mem::size_of_val(value));
+}
+
+/// Returns if both references point to the same [`referent`].
+pub(crate) fn same_referent<V: ?Sized>(left: &V, right: &V) -> bool {
+    return referent(left) == referent(right);
+}
+
+/// Groups the keys of `pairs` by the [`referent`] of their reference.
+pub(crate) fn group_by_address<'p, K: 'p, V: 'p + ?Sized>(pairs: impl Iterator<Item = (&'p K, &'p V)>) -> Vec<(&'p V, Vec<&'p K>)> {
+    let mut positions: HashMap<(*const (), usize), usize> = HashMap::new();
+    let mut groups: Vec<(&'p V, Vec<&'p K>)> = Vec::new();
+    for (key, value) in pairs {
+        let position = *positions.entry(referent(value)).or_insert_with(|| {
+            groups.push((value, Vec::new()));
+            return groups.len() - 1;
+        });
+        groups[position].1.push(key);
+    }
+    return groups;
+}
+
+/// Writes the Graphviz graph of [`DataCloud::to_dot`](crate::DataCloud::to_dot).
+pub(crate) fn write_dot<K: Debug, V: Debug + ?Sized>(groups: &[(&V, Vec<K>)]) -> String {
+    let mut dot = String::from("digraph cloud {\n");
+    for (referent, (value, keys)) in groups.iter().enumerate() {
+        let shared = keys.len() > 1;
+        let style = if shared { ", color=red" } else { "" };
+        writeln!(dot, "    r{} [shape=ellipse, label=\"{}\"{}];", referent, escape(value), style).unwrap();
+        for key in keys {
+            writeln!(dot, "    \"{}\" [shape=box];", escape(key)).unwrap();
+            writeln!(dot, "    \"{}\" -> r{}{};", escape(key), referent, if shared { " [color=red]" } else { "" }).unwrap();
+        }
+    }
+    dot.push('}');
+    return dot;
+}
+
+fn escape<T: Debug + ?Sized>(value: &T) -> String {
+    return format!("{:?}", value).replace('\\', "\\\\").replace('"', "\\\"");
+}
@@ -0,0 +1,325 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    hash::{BuildHasher, Hash},
+    io::Read
+};
+
+use crate::{
+    error::SnapshotError,
+    snapshot::{self, Decode, HasherId},
+    DataCloud
+};
+
+/// The capacity of the first chunk of an arena created with [`CloudArena::new`].
+const FIRST_CHUNK: usize = 16;
+
+/// An arena that owns values and hands out references to them that live as long as the arena.
+/// 
+/// Values are moved into chunks that are never reallocated, so a value never moves once it has
+/// been allocated, and every reference stays valid until the arena is dropped. This makes the
+/// arena the owner a [`DataCloud`] needs: clouds built by [`CloudArena::cloud`] store
+/// `&'arena V` references, and [`CloudArena::alloc_insert`] allocates a value and registers it in
+/// a cloud in one step.
+/// 
+/// Values are only dropped with the arena. A DataCloud borrowing from the arena must be dropped
+/// first, which the borrow checker enforces:
+/// 
+/// ```compile_fail
+/// use cloudr::{CloudArena, DataCloud};
+/// 
+/// let cloud: DataCloud<'_, &str, String>;
+/// {
+///     let arena = CloudArena::new();
+///     cloud = arena.cloud();
+///     arena.alloc_insert(&cloud, "x", "owned".to_string());
+/// }
+/// cloud.get("x");
+/// ```
+/// 
+/// # Examples
+/// ```
+/// use cloudr::{CloudArena, DataCloud};
+/// 
+/// let arena: CloudArena<String> = CloudArena::new();
+/// let cloud: DataCloud<'_, &str, String> = arena.cloud();
+/// 
+/// for id in 0..100 {
+///     arena.alloc_insert(&cloud, if id % 2 == 0 { "even" } else { "odd" }, id.to_string());
+/// }
+/// 
+/// assert_eq!("98", cloud.get("even").unwrap());
+/// assert_eq!("99", cloud.get("odd").unwrap());
+/// assert_eq!(100, arena.len());
+/// ```
+pub struct CloudArena<V> {
+    chunks: RefCell<Vec<Vec<V>>>,
+}
+
+impl<V> CloudArena<V> {
+    /// Returns a new, empty arena.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let arena: CloudArena<i32> = CloudArena::new();
+    /// 
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn new() -> CloudArena<V> {
+        return CloudArena::with_capacity(FIRST_CHUNK);
+    }
+
+    /// Returns a new arena whose first chunk has room for at least `capacity` values. Later
+    /// chunks double in size.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let arena: CloudArena<i32> = CloudArena::with_capacity(1024);
+    /// arena.alloc(42);
+    /// 
+    /// assert_eq!(1, arena.len());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> CloudArena<V> {
+        return CloudArena {
+            chunks: RefCell::new(vec![Vec::with_capacity(capacity.max(1))]),
+        }
+    }
+
+    /// Moves a value into the arena and returns a reference to it, valid as long as the arena.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let arena = CloudArena::with_capacity(1);
+    /// let first = arena.alloc(1);
+    /// let second = arena.alloc(2);
+    /// 
+    /// assert_eq!((&1, &2), (first, second));
+    /// ```
+    pub fn alloc(&self, value: V) -> &V {
+        let mut chunks = self.chunks.borrow_mut();
+        let last = chunks.last().unwrap();
+        if last.len() == last.capacity() {
+            let capacity = last.capacity().saturating_mul(2);
+            chunks.push(Vec::with_capacity(capacity));
+        }
+        let chunk = chunks.last_mut().unwrap();
+        chunk.push(value);
+        let value: *const V = chunk.last().unwrap();
+        // SAFETY: a chunk is never pushed past its capacity, so it never reallocates and the value
+        // stays where it is until the arena, and with it the chunk, is dropped.
+        return unsafe { &*value };
+    }
+
+    /// Makes sure the next `additional` values are allocated in a single chunk.
+    pub(crate) fn reserve(&self, additional: usize) {
+        let mut chunks = self.chunks.borrow_mut();
+        let last = chunks.last().unwrap();
+        if last.capacity() - last.len() < additional {
+            let capacity = additional.max(last.capacity().saturating_mul(2));
+            chunks.push(Vec::with_capacity(capacity));
+        }
+    }
+
+    /// Moves a value into the arena and inserts a reference to it into `cloud` under `key`,
+    /// replacing any previous reference for that key. Returns the reference to the new value.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{CloudArena, DataCloud};
+    /// 
+    /// let arena = CloudArena::new();
+    /// let cloud: DataCloud<'_, String, Vec<i32>> = arena.cloud();
+    /// 
+    /// let stored = arena.alloc_insert(&cloud, "primes".to_string(), vec![2, 3, 5]);
+    /// 
+    /// assert!(std::ptr::eq(stored, cloud.get("primes").unwrap()));
+    /// ```
+    pub fn alloc_insert<'s, K, S>(&'s self, cloud: &DataCloud<'s, K, V, S>, key: K, value: V) -> &'s V
+    where
+        K: PartialEq + Eq + Hash,
+        S: BuildHasher,
+    {
+        let value = self.alloc(value);
+        cloud.insert(key, value);
+        return value;
+    }
+
+    /// Returns an empty DataCloud that can store references to the values of this arena.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{CloudArena, DataCloud};
+    /// 
+    /// let arena = CloudArena::new();
+    /// let cloud: DataCloud<'_, &str, f64> = arena.cloud();
+    /// cloud.insert("pi", arena.alloc(3.14));
+    /// 
+    /// assert_eq!(Some(&3.14), cloud.get("pi"));
+    /// ```
+    pub fn cloud<K: PartialEq + Eq + Hash>(&self) -> DataCloud<'_, K, V> {
+        return DataCloud::new();
+    }
+
+    /// Returns an empty DataCloud that can store references to the values of this arena, and
+    /// which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{CloudArena, DataCloud};
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let arena = CloudArena::new();
+    /// let cloud: DataCloud<'_, &str, i32, RandomState> = arena.cloud_with_hasher(RandomState::new());
+    /// arena.alloc_insert(&cloud, "x", 42);
+    /// 
+    /// assert_eq!(Some(&42), cloud.get("x"));
+    /// ```
+    pub fn cloud_with_hasher<K: PartialEq + Eq + Hash, S>(&self, hash_builder: S) -> DataCloud<'_, K, V, S> {
+        return DataCloud::with_hasher(hash_builder);
+    }
+
+    /// Deserializes a cloud that was serialized with `sharing`, moving its values into the arena.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{CloudArena, DataCloud};
+    /// use cloudr::serde::Sharing;
+    /// 
+    /// let arena = CloudArena::new();
+    /// let mut deserializer = serde_json::Deserializer::from_str(r#"{"values":["shared"],"keys":[["a",0],["b",0]]}"#);
+    /// let cloud: DataCloud<'_, String, String> = arena.deserialize_cloud(&mut deserializer, Sharing::Preserve).unwrap();
+    /// 
+    /// assert!(std::ptr::eq(cloud.get("a").unwrap(), cloud.get("b").unwrap()));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize_cloud<'de, K, S, D>(&self, deserializer: D, sharing: crate::serde::Sharing) -> Result<DataCloud<'_, K, V, S>, D::Error>
+    where
+        K: PartialEq + Eq + Hash + ::serde::Deserialize<'de>,
+        V: ::serde::Deserialize<'de>,
+        S: BuildHasher + Default,
+        D: ::serde::Deserializer<'de>,
+    {
+        return ::serde::de::DeserializeSeed::deserialize(crate::serde::CloudSeed::with_sharing(self, sharing), deserializer);
+    }
+
+    /// Reads a snapshot written by [`DataCloud::write_snapshot`], moving its values into the arena.
+    /// Each value is allocated once, however many keys reference it.
+    /// 
+    /// Fails with [`SnapshotError::Truncated`] if the snapshot ends early, and with another
+    /// [`SnapshotError`] if it isn't a snapshot, comes from another format version or hasher, or
+    /// is corrupt.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{CloudArena, DataCloud};
+    /// use cloudr::error::SnapshotError;
+    /// 
+    /// let x = 42;
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &x);
+    /// let mut bytes = Vec::new();
+    /// cloud.write_snapshot(&mut bytes).unwrap();
+    /// 
+    /// let arena = CloudArena::new();
+    /// let truncated: Result<DataCloud<'_, String, i32>, _> = arena.read_snapshot(&bytes[..bytes.len() - 1]);
+    /// assert!(matches!(truncated, Err(SnapshotError::Truncated)));
+    /// 
+    /// let copy: DataCloud<'_, String, i32> = arena.read_snapshot(bytes.as_slice()).unwrap();
+    /// assert_eq!(Some(&42), copy.get("x"));
+    /// ```
+    pub fn read_snapshot<K, S, R>(&self, reader: R) -> Result<DataCloud<'_, K, V, S>, SnapshotError>
+    where
+        K: PartialEq + Eq + Hash + Decode,
+        V: Decode,
+        S: BuildHasher + Default + HasherId,
+        R: Read,
+    {
+        return snapshot::read(self, reader);
+    }
+
+    /// Returns the number of values allocated in the arena.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let arena = CloudArena::new();
+    /// arena.alloc("x");
+    /// arena.alloc("y");
+    /// 
+    /// assert_eq!(2, arena.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.chunks.borrow().iter().map(Vec::len).sum();
+    }
+
+    /// Returns if no value has been allocated in the arena.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let arena = CloudArena::new();
+    /// assert!(arena.is_empty());
+    /// 
+    /// arena.alloc(1);
+    /// assert!(!arena.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Returns an iterator over mutable references to the values of the arena, in allocation
+    /// order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let mut arena = CloudArena::with_capacity(1);
+    /// arena.alloc(1);
+    /// arena.alloc(2);
+    /// 
+    /// arena.iter_mut().for_each(|value| *value *= 10);
+    /// 
+    /// assert_eq!(vec![10, 20], arena.into_vec());
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        return self.chunks.get_mut().iter_mut().flatten();
+    }
+
+    /// Consumes the arena and returns its values, in allocation order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// 
+    /// let arena = CloudArena::with_capacity(1);
+    /// arena.alloc("a");
+    /// arena.alloc("b");
+    /// arena.alloc("c");
+    /// 
+    /// assert_eq!(vec!["a", "b", "c"], arena.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<V> {
+        return self.chunks.into_inner().into_iter().flatten().collect();
+    }
+}
+
+impl<V> Default for CloudArena<V> {
+    fn default() -> Self {
+        return CloudArena::new();
+    }
+}
+
+impl<V: Debug> Debug for CloudArena<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_list().entries(self.chunks.borrow().iter().flatten()).finish();
+    }
+}
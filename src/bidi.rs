@@ -0,0 +1,610 @@
+use std::{
+    borrow::Borrow,
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    fmt::{Debug, Display}
+};
+
+use indexmap::{set::Slice, IndexSet};
+
+use crate::{
+    error::MergeConflict,
+    iter::{IntoIter, Iter},
+    merge::{MergeReport, MergeResult, Resolver},
+    DataCloud,
+    DefaultHashBuilder
+};
+
+/// A DataCloud that also indexes its keys by the value they reference.
+/// 
+/// Besides the usual key lookups, [`get_by_value`](BiDataCloud::get_by_value),
+/// [`contains_value`](BiDataCloud::contains_value) and
+/// [`remove_by_value`](BiDataCloud::remove_by_value) run in constant time instead of scanning
+/// every pair. The index hashes the referenced values, so two references to equal values share
+/// an entry; store [`ByAddress`](crate::ByAddress) values to index by address instead.
+/// 
+/// Several keys can reference the same value, so a value maps to a set of keys.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::BiDataCloud;
+/// 
+/// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+/// cloud.insert("x", &1);
+/// cloud.insert("y", &2);
+/// 
+/// assert_eq!(Some(&1), cloud.get("x"));
+/// assert_eq!(["y"], *cloud.get_by_value(&2).unwrap());
+/// ```
+/// 
+/// The values only need `Hash` and `Eq` for the index, and may be unsized:
+/// ```
+/// use cloudr::BiDataCloud;
+/// 
+/// let names: BiDataCloud<'_, i32, str> = BiDataCloud::new();
+/// names.insert(1, "one");
+/// 
+/// assert_eq!([1], *names.get_by_value("one").unwrap());
+/// ```
+pub struct BiDataCloud<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S = DefaultHashBuilder> {
+    nodes: RefCell<HashMap<K, &'a V, S>>,
+    index: RefCell<HashMap<&'a V, IndexSet<K, DefaultHashBuilder>, S>>,
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized> BiDataCloud<'a, K, V> {
+    /// Returns a new instance of a BiDataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// ```
+    pub fn new() -> BiDataCloud<'a, K, V> {
+        return BiDataCloud::with_hasher(Default::default())
+    }
+
+    /// Returns a new instance of a BiDataCloud with room for at least `capacity` pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::with_capacity(16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> BiDataCloud<'a, K, V> {
+        return BiDataCloud::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S: Clone> BiDataCloud<'a, K, V, S> {
+    /// Returns a new instance of a BiDataCloud which will use the given hash builder to hash both
+    /// keys and values.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32, RandomState> = BiDataCloud::with_hasher(RandomState::new());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> BiDataCloud<'a, K, V, S> {
+        return BiDataCloud {
+            nodes: RefCell::new(HashMap::with_hasher(hash_builder.clone())),
+            index: RefCell::new(HashMap::with_hasher(hash_builder)),
+        }
+    }
+
+    /// Returns a new instance of a BiDataCloud with room for at least `capacity` pairs, which
+    /// will use the given hash builder to hash both keys and values.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32, RandomState> = BiDataCloud::with_capacity_and_hasher(16, RandomState::new());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> BiDataCloud<'a, K, V, S> {
+        return BiDataCloud {
+            nodes: RefCell::new(HashMap::with_capacity_and_hasher(capacity, hash_builder.clone())),
+            index: RefCell::new(HashMap::with_capacity_and_hasher(capacity, hash_builder)),
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S> BiDataCloud<'a, K, V, S> {
+    /// Returns the number of key-value pairs in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert_eq!(1, cloud.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.nodes.borrow().len();
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.borrow().is_empty();
+    }
+
+    /// Clears the cloud and its index, removing all key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// cloud.clear();
+    /// 
+    /// assert!(!cloud.contains_value(&63));
+    /// ```
+    pub fn clear(&self) {
+        self.nodes.borrow_mut().clear();
+        self.index.borrow_mut().clear();
+    }
+
+    /// Returns a borrowing iterator over the key-value pairs of the cloud, in arbitrary order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// for (key, value) in cloud.iter() {
+    ///     assert_eq!(("x", &63), (key.as_str(), value));
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, 'a, K, V> {
+        return Iter::new(self.nodes.borrow());
+    }
+
+    /// Converts the cloud into a vector of its key-value pairs, in arbitrary order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert_eq!(vec![("x".to_string(), &63)], cloud.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a V)> {
+        return self.nodes.into_inner().into_iter().collect();
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Hash + Eq + ?Sized, S: BuildHasher> BiDataCloud<'a, K, V, S> {
+    /// Inserts a key-value pair into the cloud and indexes the key under its value.
+    /// If the key was already present, its old reference is unindexed and returned.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// assert_eq!(Some(&1), cloud.insert("x", &2));
+    /// assert!(!cloud.contains_value(&1));
+    /// ```
+    pub fn insert(&self, key: K, value: &'a V) -> Option<&'a V> {
+        let mut index = self.index.borrow_mut();
+        let previous = self.nodes.borrow_mut().insert(key.clone(), value);
+        if let Some(previous) = previous {
+            unlink(&mut index, &key, previous);
+        }
+        index.entry(value).or_default().insert(key);
+        return previous;
+    }
+
+    /// Gets the reference stored under a key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert_eq!(Some(&63), cloud.get("x"));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow().get(key).copied();
+    }
+
+    /// Returns the keys that reference a value equal to `value`.
+    /// 
+    /// Keys come in the order they were indexed, except that unindexing a key moves the last
+    /// key of its value into its place. The keys stay behind a `Ref` guard, so the cloud can't be modified until it is dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &1);
+    /// 
+    /// assert_eq!(["a", "b"], *cloud.get_by_value(&1).unwrap());
+    /// assert!(cloud.get_by_value(&2).is_none());
+    /// ```
+    pub fn get_by_value(&self, value: &V) -> Option<Ref<'_, Slice<K>>> {
+        return Ref::filter_map(self.index.borrow(), |index| index.get(value).map(IndexSet::as_slice)).ok();
+    }
+
+    /// Removes a key from the cloud and its index, returning the reference it held.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert_eq!(Some(&63), cloud.remove("x"));
+    /// assert!(!cloud.contains_value(&63));
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut index = self.index.borrow_mut();
+        let value = self.nodes.borrow_mut().remove(key)?;
+        unlink(&mut index, key, value);
+        return Some(value);
+    }
+
+    /// Removes every key that references a value equal to `value`, and returns those keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &1);
+    /// cloud.insert("c", &2);
+    /// 
+    /// assert_eq!(vec!["a", "b"], cloud.remove_by_value(&1));
+    /// assert_eq!(1, cloud.len());
+    /// ```
+    pub fn remove_by_value(&self, value: &V) -> Vec<K> {
+        let keys = self.index.borrow_mut().remove(value).unwrap_or_default();
+        let mut nodes = self.nodes.borrow_mut();
+        for key in &keys {
+            nodes.remove(key);
+        }
+        return keys.into_iter().collect();
+    }
+
+    /// Returns if the cloud contains a reference indexed by this key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow().contains_key(key);
+    }
+
+    /// Returns if some key references a value equal to `value`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, String, i32> = BiDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert!(cloud.contains_value(&63));
+    /// ```
+    pub fn contains_value(&self, value: &V) -> bool {
+        return self.index.borrow().contains_key(value);
+    }
+
+    /// Retains only the key-value pairs that match the predicate, and unindexes the others.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// 
+    /// cloud.retain(|_, value| **value != 2);
+    /// 
+    /// assert!(cloud.contains_value(&1));
+    /// assert!(!cloud.contains_value(&2));
+    /// ```
+    pub fn retain<F>(&self, mut predicate: F)
+    where
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        let mut index = self.index.borrow_mut();
+        self.nodes.borrow_mut().retain(|key, value| {
+            let keep = predicate(key, value);
+            if !keep {
+                unlink(&mut index, key, value);
+            }
+            return keep;
+        });
+    }
+
+    /// Merges the other cloud into this one, keeping its references for shared keys.
+    /// Use [`merge_in_place_by`](BiDataCloud::merge_in_place_by) to pick another resolver.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// other.insert("x", &2);
+    /// 
+    /// cloud.merge_in_place(other);
+    /// 
+    /// assert!(!cloud.contains_value(&1));
+    /// assert_eq!(["x"], *cloud.get_by_value(&2).unwrap());
+    /// ```
+    pub fn merge_in_place(&self, other: BiDataCloud<'a, K, V, S>) {
+        for (key, value) in other.nodes.into_inner() {
+            self.insert(key, value);
+        }
+    }
+
+    /// Merges the other cloud into this one, resolving every shared key with `resolver`.
+    /// 
+    /// If the resolver refuses a conflict, this cloud is left untouched and the refused key is
+    /// returned in the error.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// use cloudr::merge::{ErrorOnConflict, KeepLeft};
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// other.insert("x", &2);
+    /// other.insert("y", &3);
+    /// 
+    /// let refused = cloud.merge_in_place_by(other, ErrorOnConflict).unwrap_err();
+    /// assert_eq!("x", refused.key);
+    /// assert!(!cloud.contains_value(&3));
+    /// 
+    /// let other: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// other.insert("x", &2);
+    /// other.insert("y", &3);
+    /// 
+    /// let report = cloud.merge_in_place_by(other, KeepLeft).unwrap();
+    /// assert_eq!(vec!["x"], report.conflicts);
+    /// assert!(!cloud.contains_value(&2));
+    /// assert_eq!(["y"], *cloud.get_by_value(&3).unwrap());
+    /// ```
+    pub fn merge_in_place_by<R>(&self, other: BiDataCloud<'a, K, V, S>, mut resolver: R) -> Result<MergeReport<K>, MergeConflict<K>>
+    where
+        R: Resolver<K, &'a V>,
+    {
+        let mut report = MergeReport::new();
+        let mut resolved = Vec::new();
+        {
+            let nodes = self.nodes.borrow();
+            for (key, value) in other.nodes.into_inner() {
+                match nodes.get(&key) {
+                    Some(&existing) => {
+                        let Some(value) = resolver.resolve(&key, existing, value) else {
+                            return Err(MergeConflict { key });
+                        };
+                        report.conflicts.push(key.clone());
+                        resolved.push((key, value));
+                    }
+                    None => resolved.push((key, value)),
+                }
+            }
+        }
+        for (key, value) in resolved {
+            self.insert(key, value);
+        }
+        return Ok(report);
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Hash + Eq + ?Sized, S: BuildHasher + Clone> BiDataCloud<'a, K, V, S> {
+    /// Merges the cloud with another and returns the resulting one. The other cloud always has
+    /// priority for shared keys. Use [`merge_by`](BiDataCloud::merge_by) to pick another resolver.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// other.insert("y", &2);
+    /// 
+    /// let merged = cloud.merge(&other);
+    /// assert_eq!(["y"], *merged.get_by_value(&2).unwrap());
+    /// ```
+    pub fn merge(&self, other: &BiDataCloud<'a, K, V, S>) -> BiDataCloud<'a, K, V, S> {
+        let merged = self.clone();
+        for (key, &value) in other.nodes.borrow().iter() {
+            merged.insert(key.clone(), value);
+        }
+        return merged;
+    }
+
+    /// Merges the cloud with another one and returns the resulting one, resolving every shared
+    /// key with `resolver`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BiDataCloud;
+    /// use cloudr::merge::KeepLeft;
+    /// 
+    /// let cloud: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: BiDataCloud<'_, &str, i32> = BiDataCloud::new();
+    /// other.insert("x", &2);
+    /// 
+    /// let (merged, report) = cloud.merge_by(&other, KeepLeft).unwrap();
+    /// 
+    /// assert_eq!(vec!["x"], report.conflicts);
+    /// assert!(!merged.contains_value(&2));
+    /// ```
+    pub fn merge_by<R>(&self, other: &BiDataCloud<'a, K, V, S>, resolver: R) -> MergeResult<BiDataCloud<'a, K, V, S>, K>
+    where
+        R: Resolver<K, &'a V>,
+    {
+        let merged = self.clone();
+        let report = merged.merge_in_place_by(other.clone(), resolver)?;
+        return Ok((merged, report));
+    }
+}
+
+/// Removes `key` from the keys indexed under `value`, and drops the entry once no key is left.
+fn unlink<'a, K, Q, V, S>(index: &mut HashMap<&'a V, IndexSet<K, DefaultHashBuilder>, S>, key: &Q, value: &'a V)
+where
+    K: Borrow<Q> + Hash + Eq,
+    Q: Hash + Eq + ?Sized,
+    V: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    let Some(keys) = index.get_mut(value) else {
+        return;
+    };
+    keys.swap_remove(key);
+    if keys.is_empty() {
+        index.remove(value);
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Hash + Eq + ?Sized, S: BuildHasher> Extend<(K, &'a V)> for BiDataCloud<'a, K, V, S> {
+    fn extend<T: IntoIterator<Item = (K, &'a V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Hash + Eq + ?Sized, S: BuildHasher + Clone + Default> FromIterator<(K, &'a V)> for BiDataCloud<'a, K, V, S> {
+    fn from_iter<T: IntoIterator<Item = (K, &'a V)>>(iter: T) -> Self {
+        let mut cloud = Self::default();
+        cloud.extend(iter);
+        return cloud;
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Hash + Eq + ?Sized, S: BuildHasher + Clone> From<DataCloud<'a, K, V, S>> for BiDataCloud<'a, K, V, S> {
+    fn from(value: DataCloud<'a, K, V, S>) -> Self {
+        let nodes = value.nodes.into_inner();
+        let cloud = BiDataCloud::with_capacity_and_hasher(nodes.len(), nodes.hasher().clone());
+        for (key, value) in nodes {
+            cloud.insert(key, value);
+        }
+        return cloud;
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S> From<BiDataCloud<'a, K, V, S>> for DataCloud<'a, K, V, S> {
+    fn from(value: BiDataCloud<'a, K, V, S>) -> Self {
+        return DataCloud::from_hashmap(value.nodes.into_inner());
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S: Clone + Default> Default for BiDataCloud<'a, K, V, S> {
+    fn default() -> Self {
+        return Self::with_hasher(S::default());
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Hash + Eq + ?Sized, S: Clone> Clone for BiDataCloud<'a, K, V, S> {
+    fn clone(&self) -> Self {
+        return Self {
+            nodes: self.nodes.clone(),
+            index: self.index.clone(),
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S> IntoIterator for BiDataCloud<'a, K, V, S> {
+    type IntoIter = IntoIter<K, &'a V>;
+    type Item = (K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.nodes.into_inner())
+    }
+}
+
+impl<'c, 'a, K: PartialEq + Eq + Hash, V: Hash + Eq + ?Sized, S> IntoIterator for &'c BiDataCloud<'a, K, V, S> {
+    type IntoIter = Iter<'c, 'a, K, V>;
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self.nodes.borrow())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Debug, V: Hash + Eq + Debug + ?Sized, S> Debug for BiDataCloud<'a, K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = String::from("BiCloud {\n");
+        for (k, v) in self.nodes.borrow().iter() {
+            output.push_str(&format!("    ({:?}: {:?}), \n", k, v));
+        }
+        output.remove(output.len() - 1);
+        output.push('}');
+        write!(f, "{}", output)
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Display, V: Hash + Eq + Display + ?Sized, S> Display for BiDataCloud<'a, K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = String::from("{\n");
+        for (k, v) in self.nodes.borrow().iter() {
+            output.push_str(&format!("    ({}: {}), \n", k, v));
+        }
+        output.remove(output.len() - 1);
+        output.push('}');
+        write!(f, "{}", output)
+    }
+}
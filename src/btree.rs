@@ -0,0 +1,493 @@
+use std::{
+    borrow::Borrow,
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    fmt::{Debug, Display},
+    ops::RangeBounds
+};
+
+use crate::{
+    iter::{Range, SortedIntoIter, SortedIter},
+    DataCloud
+};
+
+/// A DataCloud that keeps its keys sorted.
+/// 
+/// Backed by a `BTreeMap`, it iterates in key order and answers range queries. Like a
+/// [`DataCloud`], it only stores `&'a V` references and never moves the values.
+/// 
+/// Its comparison traits are canonical: two clouds compare like the sorted sequences of their
+/// pairs, comparing the referenced values rather than their addresses.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::BTreeDataCloud;
+/// 
+/// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+/// cloud.insert(3, &"c");
+/// cloud.insert(1, &"a");
+/// cloud.insert(2, &"b");
+/// 
+/// let keys: Vec<i32> = cloud.range(2..).map(|(key, _)| *key).collect();
+/// assert_eq!(vec![2, 3], keys);
+/// ```
+/// 
+/// The values don't need to implement `Eq`, or even to be sized; only the comparison traits ask
+/// for it:
+/// ```
+/// use cloudr::BTreeDataCloud;
+/// 
+/// trait Handler {
+///     fn handle(&self, input: i32) -> i32;
+/// }
+/// 
+/// struct Double;
+/// impl Handler for Double {
+///     fn handle(&self, input: i32) -> i32 { input * 2 }
+/// }
+/// 
+/// let handlers: BTreeDataCloud<'_, &str, dyn Handler> = BTreeDataCloud::new();
+/// handlers.insert("double", &Double);
+/// assert_eq!(42, handlers.get("double").unwrap().handle(21));
+/// ```
+pub struct BTreeDataCloud<'a, K: Ord, V: ?Sized> {
+    nodes: RefCell<BTreeMap<K, &'a V>>,
+}
+
+impl<'a, K: Ord, V: ?Sized> BTreeDataCloud<'a, K, V> {
+    /// Returns a new instance of a BTreeDataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// ```
+    pub fn new() -> BTreeDataCloud<'a, K, V> {
+        return BTreeDataCloud {
+            nodes: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Inserts a new key into the cloud, returning the reference it replaced, if any.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// 
+    /// assert_eq!(None, cloud.insert("y".to_string(), &3));
+    /// assert_eq!(Some(&3), cloud.insert("y".to_string(), &4));
+    /// ```
+    pub fn insert(&self, key: K, value: &'a V) -> Option<&'a V> {
+        return self.nodes.borrow_mut().insert(key, value);
+    }
+
+    /// Gets the reference stored under the given key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// cloud.insert("y".to_string(), &3);
+    /// 
+    /// assert_eq!(Some(&3), cloud.get("y"));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        return self.nodes.borrow().get(key).copied()
+    }
+
+    /// Removes a key from the cloud, returning the reference stored under it.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// cloud.insert("y".to_string(), &3);
+    /// 
+    /// assert_eq!(Some(&3), cloud.remove("y"));
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        return self.nodes.borrow_mut().remove(key)
+    }
+
+    /// Returns if the cloud contains the specified key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// cloud.insert("y".to_string(), &3);
+    /// 
+    /// assert!(cloud.contains_key("y"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        return self.nodes.borrow().contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// cloud.insert("y".to_string(), &3);
+    /// 
+    /// assert_eq!(1, cloud.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.nodes.borrow().len();
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.borrow().is_empty();
+    }
+
+    /// Removes every key-value pair from the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// cloud.insert("y".to_string(), &3);
+    /// cloud.clear();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.nodes.borrow_mut().clear();
+    }
+
+    /// Returns the pair with the smallest key, with the key behind a `Ref` guard.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// let (key, value) = cloud.first_key_value().unwrap();
+    /// assert_eq!((1, &"a"), (*key, value));
+    /// ```
+    pub fn first_key_value(&self) -> Option<(Ref<'_, K>, &'a V)> {
+        let nodes = self.nodes.borrow();
+        let (_, &value) = nodes.first_key_value()?;
+        return Some((Ref::map(nodes, |nodes| nodes.first_key_value().unwrap().0), value))
+    }
+
+    /// Returns the pair with the largest key, with the key behind a `Ref` guard.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// let (key, value) = cloud.last_key_value().unwrap();
+    /// assert_eq!((2, &"b"), (*key, value));
+    /// ```
+    pub fn last_key_value(&self) -> Option<(Ref<'_, K>, &'a V)> {
+        let nodes = self.nodes.borrow();
+        let (_, &value) = nodes.last_key_value()?;
+        return Some((Ref::map(nodes, |nodes| nodes.last_key_value().unwrap().0), value))
+    }
+
+    /// Removes and returns the pair with the smallest key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// assert_eq!(Some((1, &"a")), cloud.pop_first());
+    /// ```
+    pub fn pop_first(&self) -> Option<(K, &'a V)> {
+        return self.nodes.borrow_mut().pop_first();
+    }
+
+    /// Removes and returns the pair with the largest key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// assert_eq!(Some((2, &"b")), cloud.pop_last());
+    /// ```
+    pub fn pop_last(&self) -> Option<(K, &'a V)> {
+        return self.nodes.borrow_mut().pop_last();
+    }
+
+    /// Returns an iterator over the pairs whose keys fall in `range`, in key order.
+    /// 
+    /// The cloud stays borrowed until the iterator and every key it yielded are dropped.
+    /// 
+    /// # Panics
+    /// Panics if the range starts after it ends, or if both of its ends are excluded and equal.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// use std::ops::Bound;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, String, i32> = BTreeDataCloud::new();
+    /// cloud.insert("apple".to_string(), &1);
+    /// cloud.insert("banana".to_string(), &2);
+    /// cloud.insert("cherry".to_string(), &3);
+    /// 
+    /// let bounds = (Bound::Included("b"), Bound::Excluded("c"));
+    /// let values: Vec<&i32> = cloud.range::<str, _>(bounds).map(|(_, value)| value).collect();
+    /// assert_eq!(vec![&2], values);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, 'a, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        return Range::new(self.nodes.borrow(), range);
+    }
+
+    /// Splits the cloud in two at the given key. Pairs whose keys are greater than or equal to
+    /// `key` are moved into the returned cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(1, &"a");
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(3, &"c");
+    /// 
+    /// let upper = cloud.split_off(&2);
+    /// 
+    /// assert_eq!(vec![(1, &"a")], cloud.into_vec());
+    /// assert_eq!(vec![(2, &"b"), (3, &"c")], upper.into_vec());
+    /// ```
+    pub fn split_off<Q>(&self, key: &Q) -> BTreeDataCloud<'a, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        return BTreeDataCloud {
+            nodes: RefCell::new(self.nodes.borrow_mut().split_off(key)),
+        }
+    }
+
+    /// Retains only the pairs for which `predicate` returns `true`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, i32> = BTreeDataCloud::new();
+    /// cloud.insert(1, &10);
+    /// cloud.insert(2, &20);
+    /// 
+    /// cloud.retain(|_, value| **value > 15);
+    /// 
+    /// assert_eq!(vec![(2, &20)], cloud.into_vec());
+    /// ```
+    pub fn retain<F>(&self, mut predicate: F)
+    where
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        self.nodes.borrow_mut().retain(|key, value| predicate(key, value));
+    }
+
+    /// Returns an iterator over the pairs of the cloud in key order, with each key behind a `Ref` guard.
+    /// 
+    /// The cloud stays borrowed until the iterator and every key it yielded are dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// let keys: Vec<i32> = cloud.iter().rev().map(|(key, _)| *key).collect();
+    /// assert_eq!(vec![2, 1], keys);
+    /// ```
+    pub fn iter(&self) -> SortedIter<'_, 'a, K, V> {
+        return SortedIter::new(self.nodes.borrow());
+    }
+
+    /// Consumes the cloud and returns its pairs in key order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::BTreeDataCloud;
+    /// 
+    /// let cloud: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// assert_eq!(vec![(1, &"a"), (2, &"b")], cloud.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a V)> {
+        return self.nodes.into_inner().into_iter().collect();
+    }
+}
+
+impl<'a, K: Ord, V: ?Sized> Extend<(K, &'a V)> for BTreeDataCloud<'a, K, V> {
+    fn extend<T: IntoIterator<Item = (K, &'a V)>>(&mut self, iter: T) {
+        self.nodes.get_mut().extend(iter);
+    }
+}
+
+impl<'a, K: Ord, V: ?Sized> FromIterator<(K, &'a V)> for BTreeDataCloud<'a, K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, &'a V)>>(iter: T) -> Self {
+        return Self {
+            nodes: RefCell::new(BTreeMap::from_iter(iter)),
+        }
+    }
+}
+
+impl<'a, K: Ord, V: ?Sized> Default for BTreeDataCloud<'a, K, V> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<'a, K: Ord + Hash, V: ?Sized, S> From<DataCloud<'a, K, V, S>> for BTreeDataCloud<'a, K, V> {
+    /// Sorts the pairs of a DataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{BTreeDataCloud, DataCloud};
+    /// 
+    /// let cloud: DataCloud<'_, i32, &str> = DataCloud::new();
+    /// cloud.insert(2, &"b");
+    /// cloud.insert(1, &"a");
+    /// 
+    /// let sorted = BTreeDataCloud::from(cloud);
+    /// assert_eq!(vec![(1, &"a"), (2, &"b")], sorted.into_vec());
+    /// ```
+    fn from(cloud: DataCloud<'a, K, V, S>) -> Self {
+        return cloud.into_iter().collect();
+    }
+}
+
+impl<'a, K: Ord + Hash, V: ?Sized, S: BuildHasher + Default> From<BTreeDataCloud<'a, K, V>> for DataCloud<'a, K, V, S> {
+    /// Moves the pairs of a BTreeDataCloud into a hash-based DataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{BTreeDataCloud, DataCloud};
+    /// 
+    /// let sorted: BTreeDataCloud<'_, i32, &str> = BTreeDataCloud::new();
+    /// sorted.insert(1, &"a");
+    /// 
+    /// let cloud: DataCloud<'_, i32, &str> = DataCloud::from(sorted);
+    /// assert_eq!(Some(&"a"), cloud.get(&1));
+    /// ```
+    fn from(cloud: BTreeDataCloud<'a, K, V>) -> Self {
+        return DataCloud::from_hashmap(cloud.nodes.into_inner().into_iter().collect::<HashMap<K, &'a V, S>>());
+    }
+}
+
+impl<'a, K: Ord, V: ?Sized> IntoIterator for BTreeDataCloud<'a, K, V> {
+    type IntoIter = SortedIntoIter<K, &'a V>;
+    type Item = (K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        SortedIntoIter::new(self.nodes.into_inner())
+    }
+}
+
+impl<'c, 'a, K: Ord, V: ?Sized> IntoIterator for &'c BTreeDataCloud<'a, K, V> {
+    type IntoIter = SortedIter<'c, 'a, K, V>;
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        SortedIter::new(self.nodes.borrow())
+    }
+}
+
+impl<'a, K: Ord, V: PartialEq + ?Sized> PartialEq for BTreeDataCloud<'a, K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        return *self.nodes.borrow() == *other.nodes.borrow()
+    }
+}
+
+impl<'a, K: Ord, V: Eq + ?Sized> Eq for BTreeDataCloud<'a, K, V> { }
+
+impl<'a, K: Ord, V: PartialOrd + ?Sized> PartialOrd for BTreeDataCloud<'a, K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return self.nodes.borrow().partial_cmp(&*other.nodes.borrow())
+    }
+}
+
+impl<'a, K: Ord, V: Ord + ?Sized> Ord for BTreeDataCloud<'a, K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.nodes.borrow().cmp(&*other.nodes.borrow())
+    }
+}
+
+impl<'a, K: Ord + Debug, V: Debug + ?Sized> Debug for BTreeDataCloud<'a, K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = String::from("BTreeCloud {\n");
+        for (k, v) in self.nodes.borrow().iter() {
+            output.push_str(&format!("    ({:?}: {:?}), \n", k, v));
+        }
+        output.remove(output.len() - 1);
+        output.push('}');
+        write!(f, "{}", output)
+    }
+}
+
+impl<'a, K: Ord + Display, V: Display + ?Sized> Display for BTreeDataCloud<'a, K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = String::from("{\n");
+        for (k, v) in self.nodes.borrow().iter() {
+            output.push_str(&format!("    ({}: {}), \n", k, v));
+        }
+        output.remove(output.len() - 1);
+        output.push('}');
+        write!(f, "{}", output)
+    }
+}
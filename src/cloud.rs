@@ -1,24 +1,59 @@
 use std::{
-    cell::RefCell,
+    borrow::Borrow,
+    cell::{Cell, Ref, RefCell, RefMut},
     hash::{Hash, Hasher, BuildHasher},
     fmt::{Debug, Display}, collections::{HashMap, VecDeque},
-    ops::{Index, IndexMut}
+    io::Write,
+    ops::{Deref, DerefMut, Index}
 };
 
-use fxhash::{FxBuildHasher, FxHashMap};
+#[cfg(feature = "fxhash")]
+use fxhash::FxBuildHasher;
 
 use crate::{
+    alias::{self, DedupPolicy},
+    defer::{Deferring, Pending, Reentry, Settle},
     iter::{
         IntoPairs,
+        IntoRawPairs,
         IntoIter,
+        MutIntoIter,
+        IntoKeys,
+        IntoValues,
         Map,
         Iter,
-        IterMut
+        IterMut,
+        Keys,
+        KeysForPtr,
+        Values,
+        Drain,
+        ExtractIf,
+        Intersection,
+        Difference,
+        SymmetricDifference
     }, 
-    error::NullPointerError
+    diff::{self, CloudDiff, Comparison},
+    entry::Entry,
+    error::{CloudError, MergeConflict, NullPointerError, OccupiedError, SnapshotError},
+    merge::{resolve_into, MergeReport, MergeResult, Resolver},
+    snapshot::{self, Encode, HasherId}
 };
 
-pub trait IntoOwned<K, V, S = FxBuildHasher> {
+/// The hasher used by clouds that don't name one.
+/// 
+/// This is `FxBuildHasher` when the `fxhash` feature is enabled (the default), and the
+/// standard library's SipHash-based `RandomState` otherwise.
+#[cfg(feature = "fxhash")]
+pub type DefaultHashBuilder = FxBuildHasher;
+
+/// The hasher used by clouds that don't name one.
+/// 
+/// This is `FxBuildHasher` when the `fxhash` feature is enabled (the default), and the
+/// standard library's SipHash-based `RandomState` otherwise.
+#[cfg(not(feature = "fxhash"))]
+pub type DefaultHashBuilder = std::collections::hash_map::RandomState;
+
+pub trait IntoOwned<K, V, S = DefaultHashBuilder> {
     /// Returns an owned form of the object.
     /// 
     /// ```
@@ -34,13 +69,36 @@ pub trait IntoOwned<K, V, S = FxBuildHasher> {
     /// 
     /// let map: FxHashMap<String, String> = data.into_owned();
     /// ```
+    #[allow(clippy::wrong_self_convention)]
     fn into_owned(&self) -> HashMap<K, V, S>;
 }
 
 pub trait CombineWith {
+    /// The key type of the combined collections.
+    type Key;
+    /// What the combined collections store under each key.
+    type Ref;
+
     fn combine_with(&self, others: Vec<Self>) -> Self
     where
         Self: Sized;
+
+    /// Combines this collection with others, merging them from left to right and resolving every
+    /// shared key with `resolver`.
+    fn combine_with_by<R>(&self, others: Vec<Self>, resolver: R) -> MergeResult<Self, Self::Key>
+    where
+        Self: Sized,
+        R: Resolver<Self::Key, Self::Ref>;
+}
+
+/// Which of two clouds a set operation takes its values from, for keys both of them hold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The cloud the method was called on.
+    #[default]
+    Left,
+    /// The cloud passed as an argument.
+    Right,
 }
 
 pub trait AsPointer {
@@ -49,6 +107,19 @@ pub trait AsPointer {
 
 /// An abstract data structure can store values without moving them.
 /// 
+/// A DataCloud only ever hands out shared access to its values; use a [`MutDataCloud`]
+/// to store `&'a mut V` references instead.
+/// 
+/// A DataCloud can be sent to another thread when its keys, hasher and referenced values allow it,
+/// but it can't be shared between threads. Use a [`SyncDataCloud`](crate::SyncDataCloud) for that.
+/// 
+/// ```compile_fail
+/// use cloudr::DataCloud;
+/// 
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<DataCloud<'static, String, i32>>();
+/// ```
+/// 
 /// # Examples
 /// ```
 /// use cloudr::DataCloud;
@@ -59,13 +130,69 @@ pub trait AsPointer {
 /// data.insert("x".to_string(), &x);
 /// data.insert("y".to_string(), &y);
 /// 
-/// assert_eq!(&y, data.get(&"y".to_string()).unwrap());
+/// assert_eq!(&y, data.get("y").unwrap());
+/// ```
+/// 
+/// The closures passed to `retain`, `map`, `merge_in_place_by` and `dedup_aliases` may call
+/// back into the same cloud. While they run, `insert`, `remove`, `clear` and the other shared
+/// mutators are queued instead of applied. Key lookups like `get` and `contains_key` see the
+/// queued changes, while `len` and the iterators see the cloud as it was when the outermost of
+/// those calls started. The queue is applied once the outermost call returns, with the changes
+/// made by the closures winning over the call's own; it is discarded if a closure panics.
+/// Methods that need exclusive access, like `entry`, `drain` and `extract_if`, still panic when
+/// called from a closure.
+/// 
+/// ```
+/// use cloudr::DataCloud;
+/// 
+/// let (x, y) = (1, 2);
+/// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+/// cloud.insert("x", &x);
+/// 
+/// cloud.retain(|_, _| {
+///     cloud.insert("y", &y);
+///     assert!(cloud.contains_key("y"));
+///     assert_eq!(1, cloud.len());
+///     return true;
+/// });
+/// 
+/// assert_eq!(Some(&2), cloud.get("y"));
+/// ```
+/// 
+/// The values don't need to implement `Eq`, or even to be sized: only the methods that compare
+/// values, like `contains_value`, `diff` and `==`, ask for `PartialEq`.
+/// 
+/// ```
+/// use cloudr::DataCloud;
+/// 
+/// trait Handler {
+///     fn handle(&self, input: i32) -> i32;
+/// }
+/// 
+/// struct Double;
+/// impl Handler for Double {
+///     fn handle(&self, input: i32) -> i32 { input * 2 }
+/// }
+/// 
+/// let handlers: DataCloud<'_, String, dyn Handler> = DataCloud::new();
+/// handlers.insert("double".to_string(), &Double);
+/// assert_eq!(42, handlers.get("double").unwrap().handle(21));
+/// 
+/// let names: DataCloud<'_, i32, str> = DataCloud::new();
+/// names.insert(1, "one");
+/// assert!(names.contains_value("one"));
+/// 
+/// let weights: DataCloud<'_, &str, f64> = DataCloud::new();
+/// weights.insert("pi", &3.14);
+/// assert_eq!(Some(&3.14), weights.get("pi"));
 /// ```
-pub struct DataCloud<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq, S = FxBuildHasher> {
-    nodes: RefCell<HashMap<K, &'a V, S>>,
+pub struct DataCloud<'a, K: PartialEq + Eq + Hash, V: ?Sized, S = DefaultHashBuilder> {
+    pub(crate) nodes: RefCell<HashMap<K, &'a V, S>>,
+    depth: Cell<usize>,
+    pending: RefCell<Option<Pending<'a, K, V>>>,
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized> DataCloud<'a, K, V> {
     /// Returns a new instance of a DataCloud.
     /// 
     /// # Examples
@@ -75,11 +202,79 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
     /// ```
     pub fn new() -> DataCloud<'a, K, V> {
-        return DataCloud {
-            nodes: RefCell::new(FxHashMap::default()),
+        return DataCloud::with_hasher(Default::default())
+    }
+
+    /// Returns a new instance of a DataCloud with room for at least `capacity` pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::with_capacity(16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> DataCloud<'a, K, V> {
+        return DataCloud::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> DataCloud<'a, K, V, S> {
+    /// Returns a new instance of a DataCloud which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32, RandomState> = DataCloud::with_hasher(RandomState::new());
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> DataCloud<'a, K, V, S> {
+        return DataCloud::from_hashmap(HashMap::with_hasher(hash_builder));
+    }
+
+    /// Returns a new instance of a DataCloud with room for at least `capacity` pairs,
+    /// which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    /// 
+    /// // A deterministic hasher, handy for reproducible tests.
+    /// type Deterministic = BuildHasherDefault<DefaultHasher>;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32, Deterministic> = DataCloud::with_capacity_and_hasher(16, Deterministic::default());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> DataCloud<'a, K, V, S> {
+        return DataCloud::from_hashmap(HashMap::with_capacity_and_hasher(capacity, hash_builder));
+    }
+
+    /// Builds a new DataCloud from a `HashMap<K, &'a V, S>`, keeping its hasher.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use fxhash::FxHashMap;
+    /// 
+    /// let mut map: FxHashMap<String, &i32> = FxHashMap::default();
+    /// let y = 3;
+    /// map.insert("y".to_string(), &y);
+    /// 
+    /// let cloud = DataCloud::from_hashmap(map);
+    /// ```
+    pub fn from_hashmap(hashmap: HashMap<K, &'a V, S>) -> DataCloud<'a, K, V, S> {
+        return Self {
+            nodes: RefCell::new(hashmap),
+            depth: Cell::new(0),
+            pending: RefCell::new(None),
         }
     }
+}
 
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> DataCloud<'a, K, V, S> {
     /// Inserts a new key into the cloud.
     /// 
     /// # Examples
@@ -92,11 +287,19 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// ```
     pub fn insert(&self, key: K, value: &'a V) -> Option<&'a V> {
+        if self.is_deferring() {
+            let previous = self.lookup(&self.nodes.borrow(), &key);
+            self.queue().insert(key, value);
+            return previous;
+        }
         let mut nodes = self.nodes.borrow_mut();
         nodes.insert(key, value)
     }
 
-    /// Inserts a new key into the cloud if the key doesn't already exist, and returns whether the key was inserted or not.
+    /// Inserts a new key into the cloud if the key doesn't already exist.
+    /// 
+    /// Returns `true` if the key was already present, in which case nothing was inserted,
+    /// and `false` if the reference was inserted.
     /// 
     /// # Examples
     /// ```
@@ -104,19 +307,80 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
     /// let y = 3;
+    /// # #[allow(deprecated)]
     /// cloud.or_insert("y".to_string(), &y);
     /// ```
+    #[deprecated(note = "the returned flag is inverted; use `entry(key).or_insert(value)` or `try_insert` instead")]
     pub fn or_insert(&self, key: K, value: &'a V) -> bool {
-        let mut nodes = self.nodes.borrow_mut();
-        if !nodes.contains_key(&key) {
-            nodes.insert(key, value);
-            return false
+        return self.try_insert(key, value).is_err()
+    }
+
+    /// Gets the entry of the given key for in-place insertion or modification.
+    /// 
+    /// The cloud stays mutably borrowed until the entry is consumed or dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// cloud.entry("x".to_string()).and_modify(|value| *value = &y).or_insert(&x);
+    /// 
+    /// assert_eq!(Some(&42), cloud.get("x"));
+    /// ```
+    pub fn entry(&self, key: K) -> Entry<'_, 'a, K, V, S> {
+        return Entry::new(self.nodes.borrow_mut(), key)
+    }
+
+    /// Inserts a new key into the cloud if it isn't present yet and returns the inserted reference.
+    /// 
+    /// If the key is already occupied, nothing is inserted and the error carries the stored
+    /// reference in [`CloudError::Occupied`]. If the cloud is already borrowed, for example from
+    /// inside a `retain` predicate, it fails with [`CloudError::BorrowConflict`] instead of panicking.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::error::CloudError;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// assert_eq!(Ok(&42), cloud.try_insert("x".to_string(), &x));
+    /// 
+    /// let Err(CloudError::Occupied(error)) = cloud.try_insert("x".to_string(), &y) else {
+    ///     panic!("the key is occupied");
+    /// };
+    /// assert_eq!(&42, error.existing);
+    /// assert_eq!(&24, error.value);
+    /// 
+    /// let _iter = cloud.iter();
+    /// assert_eq!(Err(CloudError::BorrowConflict), cloud.try_insert("y".to_string(), &y));
+    /// ```
+    pub fn try_insert(&self, key: K, value: &'a V) -> Result<&'a V, CloudError<'a, V>> {
+        if self.is_deferring() {
+            let nodes = self.nodes.try_borrow().map_err(|_| CloudError::BorrowConflict)?;
+            if let Some(existing) = self.lookup(&nodes, &key) {
+                return Err(OccupiedError { existing, value }.into());
+            }
+            self.queue().insert(key, value);
+            return Ok(value);
+        }
+        let nodes = self.nodes.try_borrow_mut().map_err(|_| CloudError::BorrowConflict)?;
+        return match Entry::new(nodes, key) {
+            Entry::Occupied(entry) => Err(OccupiedError { existing: entry.get(), value }.into()),
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
         }
-        true
     }
 
     /// Gets the reference stored in the cloud.
     /// 
+    /// The key may be any borrowed form of the cloud's key type, like `&str` for `String` keys.
+    /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
@@ -125,44 +389,42 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// let y = 3;
     /// cloud.insert("y".to_string(), &y);
     /// 
-    /// let y_ref: Option<&i32> = cloud.get(&"y".to_string());
+    /// let y_ref: Option<&i32> = cloud.get("y");
     /// assert_eq!(&y, y_ref.unwrap());
+    /// assert_eq!(3, cloud["y"]);
     /// ```
-    pub fn get(&self, key_to_search_for: &K) -> Option<&'a V> {
-        let nodes = self.nodes.borrow();
-        for (key, value) in nodes.iter() {
-            if key == key_to_search_for {
-                return Some(*value)
-            } else {
-                continue;
-            }
-        }
-        return None;
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.lookup(&self.nodes.borrow(), key)
     }
 
-    /// Gets the reference stored in the cloud as a mutable reference.
+    /// Gets the reference stored in the cloud, failing with [`CloudError::KeyNotFound`] if the
+    /// key is missing and with [`CloudError::BorrowConflict`] if the cloud is mutably borrowed.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
+    /// use cloudr::error::CloudError;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let mut y = 3;
-    /// cloud.insert("y".to_string(), &mut y);
+    /// cloud.insert("x".to_string(), &42);
     /// 
+    /// assert_eq!(Ok(&42), cloud.try_get("x"));
+    /// assert_eq!(Err(CloudError::KeyNotFound), cloud.try_get("y"));
     /// 
-    /// let y_ref: Option<&mut i32> = cloud.get_mut(&"y".to_string());
+    /// let _drain = cloud.drain();
+    /// assert_eq!(Err(CloudError::BorrowConflict), cloud.try_get("x"));
     /// ```
-    pub fn get_mut(&self, key_to_search_for: &K) -> Option<&'a mut V> {
-        let nodes = self.nodes.borrow();
-        for (key, value) in nodes.iter() {
-            if key == key_to_search_for {
-                return Some(unsafe { {*value as *const V as *mut V}.as_mut().unwrap() })
-            } else {
-                continue;
-            }
-        }
-        return None;
+    pub fn try_get<Q>(&self, key: &Q) -> Result<&'a V, CloudError<'a, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let nodes = self.nodes.try_borrow().map_err(|_| CloudError::BorrowConflict)?;
+        return self.lookup(&nodes, key).ok_or(CloudError::KeyNotFound);
     }
 
     /// Removes the reference stored in the cloud and returns it if it exists.
@@ -175,18 +437,56 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// let mut y = 3;
     /// cloud.insert("y".to_string(), &mut y);
     /// 
-    /// let y_ref = cloud.remove(&"y".to_string()).unwrap();
+    /// let y_ref = cloud.remove("y").unwrap();
     /// ```
-    pub fn remove(&self, key: &K) -> Option<&'a V> {
-        match self.nodes.borrow_mut().remove(key) {
-            Some(value) => {
-                return Some(value)
-            }
-            None => return None,
+    pub fn remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.is_deferring() {
+            let nodes = self.nodes.borrow();
+            return self.queue().remove(key, nodes.get_key_value(key).map(|(key, &value)| (key, value)));
+        }
+        return self.nodes.borrow_mut().remove(key)
+    }
+
+    /// Removes the reference stored in the cloud and returns it, failing with
+    /// [`CloudError::KeyNotFound`] if the key is missing and with [`CloudError::BorrowConflict`]
+    /// if the cloud is already borrowed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::error::CloudError;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// 
+    /// let keys = cloud.keys();
+    /// assert_eq!(Err(CloudError::BorrowConflict), cloud.try_remove("x"));
+    /// drop(keys);
+    /// 
+    /// assert_eq!(Ok(&42), cloud.try_remove("x"));
+    /// assert_eq!(Err(CloudError::KeyNotFound), cloud.try_remove("x"));
+    /// ```
+    pub fn try_remove<Q>(&self, key: &Q) -> Result<&'a V, CloudError<'a, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.is_deferring() {
+            let nodes = self.nodes.try_borrow().map_err(|_| CloudError::BorrowConflict)?;
+            let stored = nodes.get_key_value(key).map(|(key, &value)| (key, value));
+            return self.queue().remove(key, stored).ok_or(CloudError::KeyNotFound);
         }
+        let mut nodes = self.nodes.try_borrow_mut().map_err(|_| CloudError::BorrowConflict)?;
+        return nodes.remove(key).ok_or(CloudError::KeyNotFound);
     }
 
     /// Merges in place the DataCloud with the other one by consuming the other DataCloud.
+    /// The other DataCloud has priority on conflicting keys; use
+    /// [`merge_in_place_by`](DataCloud::merge_in_place_by) to pick another resolver.
     /// 
     /// # Examples
     /// ```
@@ -202,8 +502,76 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// cloud.merge_in_place(cloud2);
     /// ```
-    pub fn merge_in_place(&self, other: DataCloud<'a, K, V>) {
-        self.nodes.borrow_mut().extend(other.nodes.into_inner().into_iter())
+    pub fn merge_in_place(&self, other: DataCloud<'a, K, V, S>) {
+        if self.is_deferring() {
+            let mut queue = self.queue();
+            for (key, value) in other.nodes.into_inner() {
+                queue.insert(key, value);
+            }
+            return;
+        }
+        self.nodes.borrow_mut().extend(other.nodes.into_inner())
+    }
+
+    /// Merges the other DataCloud into this one, resolving every shared key with `resolver`.
+    /// 
+    /// If the resolver refuses a conflict, no pair of `other` is merged and the refused key is
+    /// returned in the error. Changes the resolver made through this cloud are still applied.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::merge::{ErrorOnConflict, KeepLeft};
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("x", &2);
+    /// other.insert("y", &3);
+    /// 
+    /// let refused = cloud.merge_in_place_by(other, ErrorOnConflict).unwrap_err();
+    /// assert_eq!("x", refused.key);
+    /// assert!(!cloud.contains_key("y"));
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("x", &2);
+    /// other.insert("y", &3);
+    /// 
+    /// let report = cloud.merge_in_place_by(other, KeepLeft).unwrap();
+    /// assert_eq!(vec!["x"], report.conflicts);
+    /// assert_eq!(Some(&1), cloud.get("x"));
+    /// assert_eq!(Some(&3), cloud.get("y"));
+    /// ```
+    pub fn merge_in_place_by<R>(&self, other: DataCloud<'a, K, V, S>, mut resolver: R) -> Result<MergeReport<K>, MergeConflict<K>>
+    where
+        K: Clone,
+        R: Resolver<K, &'a V>,
+    {
+        let (reentry, start) = self.begin();
+        let nodes = self.nodes.borrow();
+        let mut report = MergeReport::new();
+        let mut resolved = Vec::new();
+        for (key, value) in other.nodes.into_inner() {
+            match self.lookup(&nodes, &key) {
+                Some(existing) => {
+                    let Some(value) = resolver.resolve(&key, existing, value) else {
+                        return Err(MergeConflict { key });
+                    };
+                    report.conflicts.push(key.clone());
+                    resolved.push((key, value));
+                }
+                None => resolved.push((key, value)),
+            }
+        }
+        drop(nodes);
+        let mut queue = self.queue();
+        for (key, value) in resolved {
+            queue.set_since(start, key, Some(value));
+        }
+        drop(queue);
+        drop(reentry);
+        return Ok(report);
     }
 
     /// Merges in place the other DataCloud with this one by consuming this DataCloud.
@@ -224,8 +592,8 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// println!("{:?}", cloud);
     /// ```
-    pub fn merge_with(self, other: &DataCloud<'a, K, V>) {
-        other.nodes.borrow_mut().extend(self.nodes.into_inner().into_iter())
+    pub fn merge_with(self, other: &DataCloud<'a, K, V, S>) {
+        other.merge_in_place(self)
     }
 
     /// Returns if the cloud contains a reference indexed by this key.
@@ -238,10 +606,14 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// let mut x = 63;
     /// cloud.insert("x".to_string(), &mut x);
     /// 
-    /// assert!(cloud.contains_key(&"x".to_string()));
+    /// assert!(cloud.contains_key("x"));
     /// ```
-    pub fn contains_key(&self, key: &K) -> bool {
-        return self.nodes.borrow().contains_key(key)
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.lookup(&self.nodes.borrow(), key).is_some()
     }
 
     /// Returns if the cloud contains the specified reference as a value.
@@ -256,246 +628,324 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// assert!(cloud.contains_value(&x));
     /// ```
-    pub fn contains_value(&self, key: &V) -> bool {
-        return self.nodes.borrow().values().collect::<Vec<_>>().contains(&&key)
+    pub fn contains_value(&self, key: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        return self.nodes.borrow().values().any(|&value| value == key);
     }
 
-    /// Returns if the cloud does not contain any key-value pairs.
+    /// Returns if the cloud holds this exact reference, comparing addresses instead of values.
+    /// 
+    /// To make every comparison of a cloud address-based, store [`ByAddress`](crate::ByAddress)
+    /// values instead.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let (x, copy) = (63, 63);
+    /// cloud.insert("x".to_string(), &x);
     /// 
-    /// assert!(cloud.is_empty());
+    /// assert!(cloud.contains_ptr(&x));
+    /// assert!(!cloud.contains_ptr(&copy));
+    /// assert!(cloud.contains_value(&copy));
     /// ```
-    pub fn is_empty(&self) -> bool {
-        return self.nodes.borrow().is_empty();
-    }
-
-    /// Returns the cloud into an iterator of `(K, &'a V)` key-value pairs in arbitrary order.
     /// 
-    /// # Examples
+    /// The vtable of a `dyn Trait` reference doesn't matter:
     /// ```
     /// use cloudr::DataCloud;
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// trait Handler {
+    ///     fn handle(&self) -> u32;
+    /// }
     /// 
-    /// let mut x = 63;
-    /// cloud.insert("x".to_string(), &mut x);
+    /// struct Echo(u32);
     /// 
-    /// for (key, value) in cloud.into_pairs() {
-    ///     println!("({key}: {value})");
+    /// impl Handler for Echo {
+    ///     fn handle(&self) -> u32 { self.0 }
     /// }
+    /// 
+    /// let (echo, other) = (Echo(1), Echo(1));
+    /// let handlers: DataCloud<'_, &str, dyn Handler> = DataCloud::new();
+    /// handlers.insert("echo", &echo);
+    /// 
+    /// assert!(handlers.contains_ptr(&echo as &dyn Handler));
+    /// assert!(!handlers.contains_ptr(&other as &dyn Handler));
     /// ```
-    pub fn into_pairs(self) -> IntoPairs<K, &'a V> {
-        return IntoPairs::new(self.nodes.into_inner().into_iter().collect());
-    }
-
-    /// Returns the cloud into an iterator of `(K, *mut V)` key-value pairs in arbitrary order.
     /// 
-    /// # Examples
+    /// But the length of a slice does, so a slice only matches one with the same start and length:
     /// ```
     /// use cloudr::DataCloud;
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// 
-    /// let mut x = 63;
-    /// cloud.insert("x".to_string(), &mut x);
+    /// let text = String::from("hello");
+    /// let cloud: DataCloud<'_, &str, str> = DataCloud::new();
+    /// cloud.insert("h", &text[..1]);
     /// 
-    /// for (key, value) in cloud.into_pairs() {
-    ///     println!("({key}: {})", *value);
-    /// }
+    /// assert!(cloud.contains_ptr(&text[..1]));
+    /// assert!(!cloud.contains_ptr(&text[..5]));
     /// ```
-    pub unsafe fn into_raw_pairs(self) -> IntoPairs<K, *mut V> {
-        return IntoPairs::new(self.nodes.into_inner().into_iter().map(|(k, v)| (k, v as *const V as *mut V)).collect());
+    pub fn contains_ptr(&self, value: &V) -> bool {
+        return self.nodes.borrow().values().any(|&stored| alias::same_referent(stored, value));
     }
 
-    /// Clears the `DataCloud`'s key-value pairs into a fresh, new one.
+    /// Returns an iterator over the keys whose reference points to `value`, in arbitrary order.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
     /// 
-    /// let cloud: DataCloud<'_, usize, i128> = DataCloud::new();
-    /// 
-    /// let v = 54;
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// let (x, copy) = (63, 63);
+    /// cloud.insert("a", &x);
+    /// cloud.insert("b", &x);
+    /// cloud.insert("c", &copy);
     /// 
-    /// cloud.insert(3, &v);
+    /// let mut keys: Vec<&str> = cloud.keys_for_ptr(&x).map(|key| *key).collect();
+    /// keys.sort();
+    /// assert_eq!(vec!["a", "b"], keys);
+    /// ```
+    pub fn keys_for_ptr<'c>(&'c self, value: &'c V) -> KeysForPtr<'c, 'a, K, V> {
+        return KeysForPtr::new(self.nodes.borrow(), value);
+    }
+
+    /// Returns if both clouds hold the same keys with references to the same addresses.
     /// 
-    /// cloud.clear();
+    /// Unlike `==`, which compares the referenced values, this only holds when the clouds
+    /// share every referenced object.
     /// 
-    /// assert_eq!(DataCloud::new(), cloud);
+    /// # Examples
     /// ```
-    pub fn clear(&self) {
-        self.nodes.borrow_mut().clear();
+    /// use cloudr::DataCloud;
+    /// 
+    /// let (x, copy) = (63, 63);
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &x);
+    /// let same: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// same.insert("x", &x);
+    /// let equal: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// equal.insert("x", &copy);
+    /// 
+    /// assert!(cloud.ptr_eq(&same));
+    /// assert!(!cloud.ptr_eq(&equal));
+    /// assert!(cloud == equal);
+    /// ```
+    pub fn ptr_eq(&self, other: &DataCloud<'a, K, V, S>) -> bool {
+        let (nodes, other) = (self.nodes.borrow(), other.nodes.borrow());
+        return nodes.len() == other.len() && nodes.iter().all(|(key, &value)| {
+            return other.get(key).is_some_and(|&stored| alias::same_referent(stored, value));
+        });
     }
 
-    /// Inserts a new key into the cloud from a raw pointer
+    /// Returns the groups of keys whose references point to the same address, leaving out
+    /// references held by a single key. Groups and the keys in them come in arbitrary order.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use cloudr::error::NullPointerError;
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// let inserted_before: Result<Option<&i32>, NullPointerError> = unsafe {
-    ///     cloud.insert_from_raw("y".to_string(), &y as *const i32)
-    /// };
+    /// let (x, copy) = (1, 1);
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("a", &x);
+    /// cloud.insert("b", &x);
+    /// cloud.insert("c", &copy);
     /// 
+    /// let mut groups = cloud.alias_groups();
+    /// groups[0].sort();
+    /// assert_eq!(vec![vec!["a", "b"]], groups);
     /// ```
-    pub unsafe fn insert_from_raw(&self, key: K, value: *const V) -> Result<Option<&'a V>, NullPointerError> {
-        if value.is_null() {
-            return Err(NullPointerError(String::from("Tried to insert null pointer in DataCloud")));
-        }
-        let reference = unsafe { value.as_ref().unwrap() };
-        Ok(self.insert(key, reference))
-    }
-
-    /// Gets the reference stored in the cloud as a mutable raw pointer.
     /// 
-    /// # Examples
+    /// Trait objects are grouped by address alone:
     /// ```
     /// use cloudr::DataCloud;
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// cloud.insert("y".to_string(), &y);
+    /// trait Handler {
+    ///     fn handle(&self) -> u32;
+    /// }
     /// 
-    /// let pointer: Option<*mut i32> = unsafe {
-    ///     cloud.get_as_raw(&"y".to_string())
-    /// };
+    /// struct Echo(u32);
+    /// 
+    /// impl Handler for Echo {
+    ///     fn handle(&self) -> u32 { self.0 }
+    /// }
+    /// 
+    /// let (echo, other) = (Echo(1), Echo(2));
+    /// let handlers: DataCloud<'_, &str, dyn Handler> = DataCloud::new();
+    /// handlers.insert("a", &echo);
+    /// handlers.insert("b", &echo);
+    /// handlers.insert("c", &other);
+    /// 
+    /// let mut groups = handlers.alias_groups();
+    /// groups[0].sort();
+    /// assert_eq!(vec![vec!["a", "b"]], groups);
     /// ```
-    pub unsafe fn get_as_raw(&self, key_to_search_for: &K) -> Option<*mut V> {
+    pub fn alias_groups(&self) -> Vec<Vec<K>>
+    where
+        K: Clone,
+    {
         let nodes = self.nodes.borrow();
-        for (key, value) in nodes.iter() {
-            if key == key_to_search_for {
-                return Some(*value as *const V as *mut V)
-            } else {
-                continue;
-            }
-        }
-        return None;
+        return alias::group_by_address(nodes.iter().map(|(key, &value)| (key, value)))
+            .into_iter()
+            .filter(|(_, keys)| keys.len() > 1)
+            .map(|(_, keys)| keys.into_iter().cloned().collect())
+            .collect();
     }
 
-    /// Builds a new DataCloud from a `FxHashMap<K, &'a V>`.
+    /// Removes aliases so that every address is referenced by at most one key, letting `policy`
+    /// pick the key that survives in each alias group. Returns the removed keys.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use fxhash::FxHashMap;
+    /// use cloudr::alias::KeepSmallest;
     /// 
-    /// let mut map: FxHashMap<String, &i32> = FxHashMap::default();
-    /// let y = 3;
-    /// map.insert("y".to_string(), &y);
+    /// let (x, y) = (1, 2);
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("a", &x);
+    /// cloud.insert("b", &x);
+    /// cloud.insert("c", &y);
     /// 
-    /// let cloud = DataCloud::from_hashmap(map);
+    /// assert_eq!(vec!["b"], cloud.dedup_aliases(KeepSmallest));
+    /// assert!(cloud.contains_key("a"));
+    /// assert!(cloud.alias_groups().is_empty());
     /// ```
-    pub fn from_hashmap(hashmap: FxHashMap<K, &'a V>) -> DataCloud<'a, K, V> {
-        return Self {
-            nodes: RefCell::new(hashmap),
+    pub fn dedup_aliases<P>(&self, mut policy: P) -> Vec<K>
+    where
+        K: Clone,
+        P: DedupPolicy<K>,
+    {
+        let (reentry, start) = self.begin();
+        let mut removed = Vec::new();
+        for keys in self.alias_groups() {
+            let kept = policy.keep(&keys);
+            for (position, key) in keys.into_iter().enumerate() {
+                if Some(position) != kept {
+                    removed.push(key);
+                }
+            }
+        }
+        let mut queue = self.queue();
+        for key in &removed {
+            queue.set_since(start, key.clone(), None);
         }
+        drop(queue);
+        drop(reentry);
+        return removed;
     }
 
-    /// Calls a function for each key-value pair of the cloud and returns an iterator over the resulted pairs.
+    /// Returns a Graphviz DOT graph of the cloud: one box per key, one ellipse per referenced
+    /// address, and an edge from each key to what it references. Addresses shared by several
+    /// keys and their edges are drawn in red.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use cloudr::iter::Map;
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// cloud.insert("y".to_string(), &y);
+    /// let x = 1;
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("a", &x);
+    /// cloud.insert("b", &x);
     /// 
-    /// let iterator: Map<String, i32> = cloud.map(|(k, v)| { return ( k.to_uppercase(), **v - 1 ) });
+    /// let dot = cloud.to_dot();
+    /// assert!(dot.starts_with("digraph cloud {"));
+    /// assert!(dot.contains("r0 [shape=ellipse, label=\"1\", color=red];"));
+    /// assert!(dot.contains("\"\\\"a\\\"\" -> r0 [color=red];"));
     /// ```
-    pub fn map<F>(&self, f: F) -> Map<K, V>
+    pub fn to_dot(&self) -> String
     where
-        F: Fn((&K, &&'a V)) -> (K, V) {
-            let collected = self.nodes.borrow().iter().map(f).collect::<Vec<(K, V)>>();
-            return Map::new(collected);
+        K: Debug,
+        V: Debug,
+    {
+        let nodes = self.nodes.borrow();
+        return alias::write_dot(&alias::group_by_address(nodes.iter().map(|(key, &value)| (key, value))));
     }
 
-    /// Returns an iterator over the elements of the cloud as `&'a` references.
+    /// Writes a snapshot of the cloud, to be read back with [`CloudArena::read_snapshot`](crate::CloudArena::read_snapshot).
+    /// 
+    /// A snapshot starts with a 26-byte header: the [`MAGIC`](crate::snapshot::MAGIC) bytes, the
+    /// format [`VERSION`](crate::snapshot::VERSION) as a `u16`, the [`HasherId`] of `S` as a `u32`,
+    /// and the length and CRC-32 of the body as a `u64` and a `u32`, all little-endian. The body
+    /// holds every referenced value once, followed by each key and the index of its value, so
+    /// aliased keys come back aliased.
     /// 
     /// # Examples
     /// ```
-    /// use cloudr::DataCloud;
-    /// use cloudr::iter::Iter;
+    /// use cloudr::{CloudArena, DataCloud};
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// cloud.insert("y".to_string(), &y);
+    /// let shared = "shared".to_string();
+    /// let cloud: DataCloud<'_, u32, String> = DataCloud::new();
+    /// cloud.insert(1, &shared);
+    /// cloud.insert(2, &shared);
     /// 
-    /// let mut iterator: Iter<'_, String, i32> = cloud.iter();
+    /// let mut bytes = Vec::new();
+    /// cloud.write_snapshot(&mut bytes).unwrap();
     /// 
-    /// assert_eq!((&"y".to_string(), &&3), iterator.next().unwrap());
+    /// let arena = CloudArena::new();
+    /// let copy: DataCloud<'_, u32, String> = arena.read_snapshot(bytes.as_slice()).unwrap();
+    /// assert_eq!(cloud, copy);
+    /// assert_eq!(1, arena.len());
     /// ```
-    pub fn iter(&'a self) -> Iter<'a, K, V> {
-        let collected = unsafe { self.nodes.as_ptr().as_ref().unwrap() }.iter().collect::<Vec<_>>();
-        return Iter::new(collected);
+    pub fn write_snapshot<W: Write>(&self, writer: W) -> Result<(), SnapshotError>
+    where
+        K: Encode,
+        V: Encode,
+        S: HasherId,
+    {
+        return snapshot::write(self, writer);
     }
 
-    /// Returns an iterator over the elements of the cloud as `&'a mut` references.
+    /// Returns a serializable view of the cloud that treats aliased keys as `sharing` says.
+    /// Serializing the cloud itself is the same as using [`Sharing::Duplicate`](crate::serde::Sharing::Duplicate).
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use cloudr::iter::IterMut;
-    /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// cloud.insert("y".to_string(), &y);
+    /// use cloudr::serde::Sharing;
     /// 
-    /// let mut iterator: IterMut<'_, String, i32> = cloud.iter_mut();
+    /// let x = 1;
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("a", &x);
     /// 
-    /// assert_eq!((&"y".to_string(), &mut &3), iterator.next().unwrap());
+    /// let json = serde_json::to_string(&cloud.serialize_with(Sharing::Preserve)).unwrap();
+    /// assert_eq!(r#"{"values":[1],"keys":[["a",0]]}"#, json);
     /// ```
-    pub fn iter_mut(&'a self) -> IterMut<'a, K, V> {
-        let collected = unsafe { self.nodes.as_ptr().as_mut().unwrap() }.iter_mut().collect::<Vec<(&'a K, &mut &'a V)>>();
-        return IterMut::new(collected);
+    #[cfg(feature = "serde")]
+    pub fn serialize_with(&self, sharing: crate::serde::Sharing) -> crate::serde::SerializeCloud<'_, 'a, K, V, S> {
+        return crate::serde::SerializeCloud::new(self, sharing);
     }
 
-    /// Consumes the DataCloud and returns a vector of tuples containing `(K, &'a V)`.
+    /// Returns if the cloud does not contain any key-value pairs.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// cloud.insert("y".to_string(), &y);
     /// 
-    /// let mut vec: Vec<(String, &i32)> = cloud.into_vec();
-    /// assert_eq!(vec, vec![("y".to_string(), &y)]);
+    /// assert!(cloud.is_empty());
     /// ```
-    pub fn into_vec(self) -> Vec<(K, &'a V)> {
-        let collected = self.nodes.into_inner().into_iter().collect::<Vec<_>>();
-        collected
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.borrow().is_empty();
     }
 
-    /// Consumes the DataCloud and returns a VecDeque of tuples containing `(K, &'a V)`.
+    /// Returns the number of key-value pairs in the cloud.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use std::collections::VecDeque;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// let y = 3;
-    /// cloud.insert("y".to_string(), &y);
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
     /// 
-    /// let mut vec: VecDeque<(String, &i32)> = cloud.into_vecdeque();
+    /// assert_eq!(1, cloud.len());
     /// ```
-    pub fn into_vecdeque(self) -> VecDeque<(K, &'a V)> {
-        let collected = self.nodes.into_inner().into_iter().collect::<VecDeque<_>>();
-        collected
+    pub fn len(&self) -> usize {
+        return self.nodes.borrow().len();
     }
 
-    /// Inserts multiple elements at a time in the DataCloud.
+    /// Returns the cloud into an iterator of `(K, &'a V)` key-value pairs in arbitrary order.
     /// 
     /// # Examples
     /// ```
@@ -503,50 +953,56 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
     /// 
-    /// let x = 42;
-    /// let y = 24;
+    /// let mut x = 63;
+    /// cloud.insert("x".to_string(), &mut x);
     /// 
-    /// let pairs: Vec<(String, &i32)> = vec![("x".to_string(), &x), ("y".to_string(), &y)];
-    /// cloud.insert_all(pairs);
+    /// for (key, value) in cloud.into_pairs() {
+    ///     println!("({key}: {value})");
+    /// }
     /// ```
-    pub fn insert_all(&self, pairs: Vec<(K, &'a V)>) -> Vec<Option<&'a V>> {
-        let mut out = Vec::new();
-        for (key, value) in pairs {
-            out.push(self.insert(key, value));
-        }
-        out
+    pub fn into_pairs(self) -> IntoPairs<K, &'a V> {
+        return IntoPairs::new(self.nodes.into_inner());
     }
 
-    /// Gets multiple elements at a time in the DataCloud.
+    /// Consumes the cloud and returns an iterator over its keys in arbitrary order.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
     /// 
-    /// let x = 42;
-    /// let y = 24;
+    /// let keys: Vec<String> = cloud.into_keys().collect();
+    /// assert_eq!(vec!["x".to_string()], keys);
+    /// ```
+    pub fn into_keys(self) -> IntoKeys<K, &'a V> {
+        return IntoKeys::new(self.nodes.into_inner());
+    }
+
+    /// Consumes the cloud and returns an iterator over its `&'a V` values in arbitrary order.
     /// 
-    /// let x_string = "x".to_string();
-    /// let y_string = "y".to_string();
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
     /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 63;
     /// cloud.insert("x".to_string(), &x);
-    /// cloud.insert("y".to_string(), &y);
-    /// 
-    /// let pairs = vec![&x_string, &y_string];
     /// 
-    /// assert_eq!(cloud.get_all(pairs), vec![Some(&x), Some(&y)])
+    /// let values: Vec<&i32> = cloud.into_values().collect();
+    /// assert_eq!(vec![&63], values);
     /// ```
-    pub fn get_all(&self, keys: Vec<&K>) -> Vec<Option<&'a V>> {
-        let mut out = Vec::new();
-        for key in keys {
-            out.push(self.get(key));
-        }
-        out
+    pub fn into_values(self) -> IntoValues<K, &'a V> {
+        return IntoValues::new(self.nodes.into_inner());
     }
 
-     /// Gets multiple elements at a time in the DataCloud as mutable references.
+    /// Returns the cloud into an iterator of `(K, *const V)` key-value pairs in arbitrary order.
+    /// 
+    /// # Safety
+    /// The pointers are only valid for as long as the `'a` borrows they were created from,
+    /// and the pointees must never be written through them.
     /// 
     /// # Examples
     /// ```
@@ -554,342 +1010,1246 @@ impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> DataCloud<'a, K, V> {
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
     /// 
-    /// let mut x = 42;
-    /// let mut y = 24;
+    /// let mut x = 63;
+    /// cloud.insert("x".to_string(), &mut x);
     /// 
-    /// let x_string = "x".to_string();
-    /// let y_string = "y".to_string();
+    /// for (key, value) in unsafe { cloud.into_raw_pairs() } {
+    ///     println!("({key}: {})", unsafe { *value });
+    /// }
+    /// ```
+    pub unsafe fn into_raw_pairs(self) -> IntoRawPairs<'a, K, V> {
+        return IntoRawPairs::new(self.nodes.into_inner());
+    }
+
+    /// Clears the `DataCloud`'s key-value pairs into a fresh, new one.
     /// 
-    /// cloud.insert("x".to_string(), &x);
-    /// cloud.insert("y".to_string(), &y);
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
     /// 
-    /// let pairs = vec![&x_string, &y_string];
+    /// let cloud: DataCloud<'_, usize, i128> = DataCloud::new();
+    /// 
+    /// let v = 54;
+    /// 
+    /// cloud.insert(3, &v);
+    /// 
+    /// cloud.clear();
     /// 
-    /// let mut_pairs: Vec<Option<&mut i32>> = cloud.get_mut_all(pairs);
+    /// assert_eq!(DataCloud::new(), cloud);
     /// ```
-    pub fn get_mut_all(&self, keys: Vec<&K>) -> Vec<Option<&'a mut V>> {
-        let mut out = Vec::new();
-        for key in keys {
-            out.push(self.get_mut(key));
+    pub fn clear(&self) {
+        if self.is_deferring() {
+            self.queue().clear();
+            return;
         }
-        out
+        self.nodes.borrow_mut().clear();
     }
 
-    /// Returns the DataCloud as a constant pointer to a `DataCloud<'a, K, V>`.
+    /// Inserts a new key into the cloud from a raw pointer
+    /// 
+    /// # Safety
+    /// A non-null `value` must point to a valid `V` that stays alive and unmodified for `'a`.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
+    /// use cloudr::error::NullPointerError;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// let inserted_before: Result<Option<&i32>, NullPointerError> = unsafe {
+    ///     cloud.insert_from_raw("y".to_string(), &y as *const i32)
+    /// };
     /// 
-    /// let pointer: *const DataCloud<'_, String, i32> = cloud.as_ptr();
     /// ```
-    pub fn as_ptr(&self) -> *const DataCloud<'a, K, V> {
-        return self as *const DataCloud<'a, K, V>
+    pub unsafe fn insert_from_raw(&self, key: K, value: *const V) -> Result<Option<&'a V>, NullPointerError> {
+        if value.is_null() {
+            return Err(NullPointerError(String::from("Tried to insert null pointer in DataCloud")));
+        }
+        let reference = unsafe { value.as_ref().unwrap() };
+        Ok(self.insert(key, reference))
     }
 
-    /// Returns the DataCloud's inner HashMap as a boxed shared reference `Box<&HashMap<K, &'a V, S>`.
+    /// Inserts a new key into the cloud from a raw pointer, returning the reference it replaced.
+    /// 
+    /// Fails with [`CloudError::NullPointer`] if `value` is null and with
+    /// [`CloudError::BorrowConflict`] if the cloud is already borrowed.
+    /// 
+    /// # Safety
+    /// A non-null `value` must point to a valid `V` that stays alive and unmodified for `'a`.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use fxhash::FxHashMap;
+    /// use cloudr::error::CloudError;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
-    /// 
-    /// let boxed_ref: Box<&FxHashMap<String, &i32>> = cloud.as_boxed_ref();
-    /// ```
-    pub fn as_boxed_ref(&self) -> Box<&FxHashMap<K, &'a V>> {
-        return Box::new(unsafe { self.nodes.as_ptr().as_ref() }.unwrap())
+    /// let (x, y) = (1, 2);
+    /// 
+    /// assert_eq!(Ok(None), unsafe { cloud.try_insert_from_raw("x".to_string(), &x) });
+    /// assert_eq!(Ok(Some(&1)), unsafe { cloud.try_insert_from_raw("x".to_string(), &y) });
+    /// assert_eq!(Err(CloudError::NullPointer), unsafe { cloud.try_insert_from_raw("x".to_string(), std::ptr::null()) });
+    /// 
+    /// let _iter = cloud.iter();
+    /// assert_eq!(Err(CloudError::BorrowConflict), unsafe { cloud.try_insert_from_raw("y".to_string(), &y) });
+    /// ```
+    pub unsafe fn try_insert_from_raw(&self, key: K, value: *const V) -> Result<Option<&'a V>, CloudError<'a, V>> {
+        let value = unsafe { value.as_ref() }.ok_or(CloudError::NullPointer)?;
+        if self.is_deferring() {
+            let nodes = self.nodes.try_borrow().map_err(|_| CloudError::BorrowConflict)?;
+            let previous = self.lookup(&nodes, &key);
+            self.queue().insert(key, value);
+            return Ok(previous);
+        }
+        let mut nodes = self.nodes.try_borrow_mut().map_err(|_| CloudError::BorrowConflict)?;
+        return Ok(nodes.insert(key, value));
     }
 
-    /// Returns the DataCloud's inner HashMap as a boxed raw pointer `Box<*const HashMap<K, &'a V, S>`.
+    /// Gets the reference stored in the cloud as a constant raw pointer.
+    /// 
+    /// # Safety
+    /// The pointer is derived from a shared `&'a V`, so it must never be written through
+    /// and must not be dereferenced after `'a` ends.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use fxhash::FxHashMap;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
     /// 
-    /// let boxed_ptr: Box<*const FxHashMap<String, &i32>> = unsafe {
-    ///     cloud.as_boxed_ptr()
+    /// let pointer: Option<*const i32> = unsafe {
+    ///     cloud.get_as_raw("y")
     /// };
     /// ```
-    pub unsafe fn as_boxed_ptr(&self) -> Box<*const FxHashMap<K, &'a V>> {
-        return Box::new(self.nodes.as_ptr())
+    pub unsafe fn get_as_raw<Q>(&self, key: &Q) -> Option<*const V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.get(key).map(|value| value as *const V)
     }
 
-    /// Returns the DataCloud's inner HashMap as a boxed mutable reference `Box<&mut HashMap<K, &'a V, S>`.
+    /// Returns a lazy iterator that calls a function for each key-value pair of the cloud.
+    /// 
+    /// The cloud stays borrowed until the iterator is dropped. Inserts and removals the function
+    /// makes through the cloud are queued and applied when it is, while making them from
+    /// outside the function in the meantime panics.
     /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
-    /// use fxhash::FxHashMap;
     /// 
     /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
     /// 
-    /// let boxed_ref: Box<&mut FxHashMap<String, &i32>> = unsafe {
-    ///     cloud.as_boxed_mut()
-    /// };
+    /// let mut iterator = cloud.map(|(k, v)| {
+    ///     cloud.insert(k.to_uppercase(), *v);
+    ///     return ( k.to_uppercase(), **v - 1 );
+    /// });
+    /// 
+    /// assert_eq!(Some(("Y".to_string(), 2)), iterator.next());
+    /// assert!(cloud.contains_key("Y"));
+    /// drop(iterator);
+    /// assert_eq!(2, cloud.len());
     /// ```
-    pub unsafe fn as_boxed_mut(&self) -> Box<&mut FxHashMap<K, &'a V>> {
-        return Box::new(unsafe { self.nodes.as_ptr().as_mut().unwrap() })
+    pub fn map<F>(&self, f: F) -> Map<'_, 'a, K, V, F>
+    where
+        K: Clone,
+        V: Sized,
+        F: FnMut((&K, &&'a V)) -> (K, V) {
+            let nodes = self.nodes.borrow();
+            return Map::new(nodes, f, Settle::new(self));
     }
 
-    /// Retains only the elements specified by the predicate function.
-    ///
+    /// Returns an iterator over the elements of the cloud, with each key behind a `Ref` guard.
+    /// 
+    /// The cloud stays borrowed until the iterator and every key it yielded are dropped, so
+    /// inserting or removing through the cloud in the meantime panics.
+    /// 
     /// # Examples
-    ///
     /// ```
     /// use cloudr::DataCloud;
-    ///
-    /// let cloud: DataCloud<String, i32> = DataCloud::new();
-    /// cloud.insert("x".to_string(), &42);
-    /// cloud.insert("y".to_string(), &24);
-    ///
-    /// cloud.retain(|key, value| {
-    ///     *value > &30
-    /// });
-    ///
-    /// assert!(cloud.contains_key(&"x".to_string()));
-    /// assert!(!cloud.contains_key(&"y".to_string()));
+    /// use cloudr::iter::Iter;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let mut iterator: Iter<'_, '_, String, i32> = cloud.iter();
+    /// let (key, value) = iterator.next().unwrap();
+    /// 
+    /// assert_eq!(("y", &3), (key.as_str(), value));
     /// ```
-    pub fn retain<F>(&self, mut predicate: F)
-    where
-        F: FnMut(&K, &&'a V) -> bool,
-    {
-        let mut nodes = self.nodes.borrow_mut();
-        nodes.retain(|key, value| predicate(key, value));
+    /// 
+    /// Inserting while iterating panics with a borrow error instead of invalidating the iterator:
+    /// ```should_panic
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// for (key, value) in cloud.iter() {
+    ///     cloud.insert(key.to_uppercase(), value);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, 'a, K, V> {
+        return Iter::new(self.nodes.borrow());
     }
 
-    /// Returns a new DataCloud from the given vector of keys and values `Vec<(K, &'a V)>`.
+    /// Returns an iterator over the elements of the cloud as `&'a mut` references.
     /// 
     /// # Examples
-    /// 
     /// ```
     /// use cloudr::DataCloud;
-    ///
-    /// let mut vector = Vec::new();
-    /// vector.push((String::from("Banana"), &1));
+    /// use cloudr::iter::IterMut;
     /// 
-    /// let cloud: DataCloud<'_, String, i32> = DataCloud::from_vec(vector);
+    /// let mut cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let mut iterator: IterMut<'_, '_, String, i32> = cloud.iter_mut();
+    /// 
+    /// assert_eq!((&"y".to_string(), &mut &3), iterator.next().unwrap());
     /// ```
-    pub fn from_vec<T: Into<Vec<(K, &'a V)>>>(vec: T) -> Self {
-        let mut hash = FxHashMap::default();
-
-        for (k, v) in vec.into() {
-            hash.insert(k, v);
-        }
-
-        Self::from_hashmap(hash)
+    pub fn iter_mut(&mut self) -> IterMut<'_, 'a, K, V> {
+        return IterMut::new(self.nodes.get_mut());
     }
-}
 
-impl<'a, K: PartialEq + Eq + Hash + Clone, V: PartialEq + Eq + Clone> DataCloud<'a, K, V> {
-    /// Merges the DataCloud with another and returns the resulting one.
-    /// The other DataCloud will always have priority. So, if
-    /// there are two conflicting keys, the other one will always have priority.
+    /// Returns an iterator over the keys of the cloud, each behind a `Ref` guard.
+    /// 
+    /// The cloud stays borrowed until the iterator and every key it yielded are dropped.
     /// 
     /// # Examples
-    ///
     /// ```
     /// use cloudr::DataCloud;
-    ///
-    /// let cloud: DataCloud<String, i32> = DataCloud::new();
-    /// cloud.insert("x".to_string(), &42);
-    /// cloud.insert("y".to_string(), &24);
-    ///
-    /// let cloud2: DataCloud<String, i32> = DataCloud::new();
-    /// cloud2.insert("z".to_string(), &64);
     /// 
-    /// assert!(cloud.merge(&cloud2).contains_key(&"z".to_string()));
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let keys: Vec<String> = cloud.keys().map(|key| key.clone()).collect();
+    /// assert_eq!(vec!["y".to_string()], keys);
     /// ```
-    pub fn merge(&self, other: &DataCloud<'a, K, V>) -> DataCloud<'a, K, V> {
-        let new_cloud = DataCloud::new();
-        for (key, value) in self.nodes.borrow().iter() {
-            new_cloud.insert(key.clone(), value.clone());
-        }
-
-        for (key, value) in other.nodes.borrow().iter() {
-            new_cloud.insert(key.clone(), value.clone());
-        }
-
-        new_cloud
+    pub fn keys(&self) -> Keys<'_, 'a, K, V> {
+        return Keys::new(self.nodes.borrow());
     }
 
-    /// Merges the DataCloud with other instances and returns the resulting one.
-    /// The last element of the vector `others` will always have priority. So, if
-    /// there are two conflicting keys, the last one will always have priority.
+    /// Returns an iterator over the `&'a V` values of the cloud.
+    /// 
+    /// The cloud stays borrowed until the iterator is dropped, but the values it yields don't.
     /// 
     /// # Examples
-    ///
     /// ```
     /// use cloudr::DataCloud;
-    ///
-    /// let cloud: DataCloud<String, i32> = DataCloud::new();
-    /// cloud.insert("x".to_string(), &42);
-    /// cloud.insert("y".to_string(), &24);
-    ///
-    /// let cloud2: DataCloud<String, i32> = DataCloud::new();
-    /// cloud2.insert("z".to_string(), &64);
     /// 
-    /// assert!(cloud.merge_all(vec![&cloud2]).contains_key(&"z".to_string()));
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let values: Vec<&i32> = cloud.values().collect();
+    /// cloud.clear();
+    /// 
+    /// assert_eq!(vec![&3], values);
     /// ```
-    pub fn merge_all(&self, others: Vec<&DataCloud<'a, K, V>>) -> DataCloud<'a, K, V> {
-        let new_cloud = DataCloud::new();
-        for (key, value) in self.nodes.borrow().iter() {
-            new_cloud.insert(key.clone(), value.clone());
-        }
+    pub fn values(&self) -> Values<'_, 'a, K, V> {
+        return Values::new(self.nodes.borrow());
+    }
 
-        for other in others {
-            for (key, value) in other.nodes.borrow().iter() {
-                new_cloud.insert(key.clone(), value.clone());
-            }
-        }
+    /// Consumes the DataCloud and returns a vector of tuples containing `(K, &'a V)`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let mut vec: Vec<(String, &i32)> = cloud.into_vec();
+    /// assert_eq!(vec, vec![("y".to_string(), &y)]);
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a V)> {
+        return self.nodes.into_inner().into_iter().collect::<Vec<_>>();
+    }
+
+    /// Consumes the DataCloud and returns a VecDeque of tuples containing `(K, &'a V)`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use std::collections::VecDeque;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let mut vec: VecDeque<(String, &i32)> = cloud.into_vecdeque();
+    /// ```
+    pub fn into_vecdeque(self) -> VecDeque<(K, &'a V)> {
+        return self.nodes.into_inner().into_iter().collect::<VecDeque<_>>();
+    }
+
+    /// Inserts multiple elements at a time in the DataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// let pairs: Vec<(String, &i32)> = vec![("x".to_string(), &x), ("y".to_string(), &y)];
+    /// cloud.insert_all(pairs);
+    /// ```
+    pub fn insert_all(&self, pairs: Vec<(K, &'a V)>) -> Vec<Option<&'a V>> {
+        let mut out = Vec::new();
+        for (key, value) in pairs {
+            out.push(self.insert(key, value));
+        }
+        out
+    }
+
+    /// Gets multiple elements at a time in the DataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// let x_string = "x".to_string();
+    /// let y_string = "y".to_string();
+    /// 
+    /// cloud.insert("x".to_string(), &x);
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let pairs = vec![&x_string, &y_string];
+    /// 
+    /// assert_eq!(cloud.get_all(pairs), vec![Some(&x), Some(&y)])
+    /// ```
+    pub fn get_all<Q>(&self, keys: Vec<&Q>) -> Vec<Option<&'a V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut out = Vec::new();
+        for key in keys {
+            out.push(self.get(key));
+        }
+        out
+    }
+
+    /// Returns the DataCloud as a constant pointer to a `DataCloud<'a, K, V, S>`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// let pointer: *const DataCloud<'_, String, i32> = cloud.as_ptr();
+    /// ```
+    pub fn as_ptr(&self) -> *const DataCloud<'a, K, V, S> {
+        return self as *const DataCloud<'a, K, V, S>
+    }
+
+    /// Returns the DataCloud's inner HashMap as a boxed shared reference `Box<&HashMap<K, &'a V, S>`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::DefaultHashBuilder;
+    /// use std::collections::HashMap;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// let boxed_ref: Box<&HashMap<String, &i32, DefaultHashBuilder>> = cloud.as_boxed_ref();
+    /// ```
+    pub fn as_boxed_ref(&self) -> Box<&HashMap<K, &'a V, S>> {
+        return Box::new(unsafe { self.nodes.as_ptr().as_ref() }.unwrap())
+    }
+
+    /// Returns the DataCloud's inner HashMap as a boxed raw pointer `Box<*const HashMap<K, &'a V, S>`.
+    /// 
+    /// # Safety
+    /// The pointer bypasses the cloud's borrow tracking, so it must not be dereferenced
+    /// while the cloud is being mutated or after the cloud is dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::DefaultHashBuilder;
+    /// use std::collections::HashMap;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// let boxed_ptr: Box<*const HashMap<String, &i32, DefaultHashBuilder>> = unsafe {
+    ///     cloud.as_boxed_ptr()
+    /// };
+    /// ```
+    pub unsafe fn as_boxed_ptr(&self) -> Box<*const HashMap<K, &'a V, S>> {
+        return Box::new(self.nodes.as_ptr())
+    }
+
+    /// Returns the DataCloud's inner HashMap as a boxed mutable reference `Box<&mut HashMap<K, &'a V, S>`.
+    /// 
+    /// # Safety
+    /// The reference bypasses the cloud's borrow tracking: the caller must ensure no other
+    /// access to the cloud happens for as long as it is alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::DefaultHashBuilder;
+    /// use std::collections::HashMap;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// let boxed_ref: Box<&mut HashMap<String, &i32, DefaultHashBuilder>> = unsafe {
+    ///     cloud.as_boxed_mut()
+    /// };
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn as_boxed_mut(&self) -> Box<&mut HashMap<K, &'a V, S>> {
+        return Box::new(unsafe { self.nodes.as_ptr().as_mut().unwrap() })
+    }
+
+    /// Retains only the elements specified by the predicate function.
+    ///
+    /// The predicate may call back into the cloud: the pairs it rejects are removed before the
+    /// changes it makes are applied, once `retain` returns.
+    /// 
+    /// # Examples
+    ///
+    /// ```
+    /// use cloudr::DataCloud;
+    ///
+    /// let cloud: DataCloud<String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// cloud.insert("y".to_string(), &24);
+    ///
+    /// cloud.retain(|key, value| {
+    ///     *value > &30
+    /// });
+    ///
+    /// assert!(cloud.contains_key("x"));
+    /// assert!(!cloud.contains_key("y"));
+    /// ```
+    pub fn retain<F>(&self, mut predicate: F)
+    where
+        K: Clone,
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        let (reentry, start) = self.begin();
+        let nodes = self.nodes.borrow();
+        let rejected: Vec<K> = nodes.iter()
+            .filter(|&(key, value)| !predicate(key, value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        drop(nodes);
+        let mut queue = self.queue();
+        for key in rejected {
+            queue.set_since(start, key, None);
+        }
+        drop(queue);
+        drop(reentry);
+    }
+
+    /// Retains only the key-value pairs that match the predicate, failing with
+    /// [`CloudError::BorrowConflict`] if the cloud is already borrowed.
+    /// 
+    /// The predicate may call back into the cloud, like with [`retain`](DataCloud::retain).
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::error::CloudError;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &42);
+    /// cloud.insert("y", &24);
+    /// 
+    /// let iterator = cloud.iter();
+    /// assert_eq!(Err(CloudError::BorrowConflict), cloud.try_retain(|_, _| true));
+    /// drop(iterator);
+    /// 
+    /// cloud.try_retain(|_, value| {
+    ///     assert_eq!(Ok(&24), cloud.try_get("y"));
+    ///     return **value > 30;
+    /// }).unwrap();
+    /// 
+    /// assert_eq!(vec![("x", &42)], cloud.into_vec());
+    /// ```
+    pub fn try_retain<F>(&self, mut predicate: F) -> Result<(), CloudError<'a, V>>
+    where
+        K: Clone,
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        let available = match self.is_deferring() {
+            true => self.nodes.try_borrow().is_ok(),
+            false => self.nodes.try_borrow_mut().is_ok(),
+        };
+        if !available {
+            return Err(CloudError::BorrowConflict);
+        }
+        self.retain(|key, value| predicate(key, value));
+        return Ok(());
+    }
+
+    /// Tries to reserve room for at least `additional` more pairs, failing with
+    /// [`CloudError::Capacity`] if the allocation fails and with [`CloudError::BorrowConflict`]
+    /// if the cloud is already borrowed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::error::CloudError;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// assert!(cloud.try_reserve(16).is_ok());
+    /// assert!(matches!(cloud.try_reserve(usize::MAX), Err(CloudError::Capacity(_))));
+    /// ```
+    pub fn try_reserve(&self, additional: usize) -> Result<(), CloudError<'a, V>> {
+        let mut nodes = self.nodes.try_borrow_mut().map_err(|_| CloudError::BorrowConflict)?;
+        return Ok(nodes.try_reserve(additional)?);
+    }
+
+    /// Removes every key-value pair from the cloud and returns them as an iterator.
+    /// 
+    /// The cloud stays mutably borrowed until the iterator is dropped. Dropping it early still
+    /// empties the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// 
+    /// let shared = &cloud;
+    /// let drained: Vec<(String, &i32)> = shared.drain().collect();
+    /// 
+    /// assert_eq!(vec![("x".to_string(), &42)], drained);
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn drain(&self) -> Drain<'_, 'a, K, V> {
+        return Drain::new(self.nodes.borrow_mut());
+    }
+
+    /// Returns an iterator that removes and yields every key-value pair for which `predicate`
+    /// returns `true`.
+    /// 
+    /// The cloud stays mutably borrowed until the iterator is dropped. Pairs the iterator
+    /// hasn't reached when it is dropped stay in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// cloud.insert("y".to_string(), &24);
+    /// 
+    /// let extracted: Vec<(String, &i32)> = cloud.extract_if(|_, value| **value > 30).collect();
+    /// 
+    /// assert_eq!(vec![("x".to_string(), &42)], extracted);
+    /// assert!(!cloud.contains_key("x"));
+    /// assert!(cloud.contains_key("y"));
+    /// ```
+    pub fn extract_if<F>(&self, predicate: F) -> ExtractIf<'_, 'a, K, V, F>
+    where
+        F: FnMut(&K, &mut &'a V) -> bool,
+    {
+        return ExtractIf::new(self.nodes.borrow_mut(), predicate);
+    }
+
+    /// Returns the differences that lead from this cloud to the other one, comparing the values
+    /// referenced under shared keys with `PartialEq`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let before: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// before.insert("x", &1);
+    /// 
+    /// let after: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// after.insert("x", &1);
+    /// after.insert("y", &2);
+    /// 
+    /// let diff = before.diff(&after);
+    /// assert_eq!(vec![("y", &2)], diff.added);
+    /// assert!(diff.changed.is_empty());
+    /// ```
+    pub fn diff(&self, other: &DataCloud<'a, K, V, S>) -> CloudDiff<'a, K, V>
+    where
+        K: Clone,
+        V: PartialEq,
+    {
+        return diff::diff(self, other, Comparison::Value);
+    }
+
+    /// Returns the differences that lead from this cloud to the other one, deciding whether a
+    /// shared key changed with `comparison`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::diff::Comparison;
+    /// 
+    /// let (first, second) = (1, 1);
+    /// 
+    /// let before: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// before.insert("x", &first);
+    /// 
+    /// let after: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// after.insert("x", &second);
+    /// 
+    /// assert!(before.diff_by(&after, Comparison::Value).is_empty());
+    /// assert_eq!(1, before.diff_by(&after, Comparison::Address).changed.len());
+    /// ```
+    pub fn diff_by(&self, other: &DataCloud<'a, K, V, S>, comparison: Comparison) -> CloudDiff<'a, K, V>
+    where
+        K: Clone,
+        V: PartialEq,
+    {
+        return diff::diff(self, other, comparison);
+    }
+
+    /// Returns a lazy view over the pairs whose keys both clouds hold, with values taken from `side`.
+    /// 
+    /// Both clouds stay borrowed until the view and every key it yielded are dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{DataCloud, Side};
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &3);
+    /// 
+    /// let shared: Vec<(&str, &i32)> = cloud.intersection(&other, Side::Right).map(|(key, value)| (*key, value)).collect();
+    /// assert_eq!(vec![("y", &3)], shared);
+    /// ```
+    pub fn intersection<'c>(&'c self, other: &'c DataCloud<'a, K, V, S>, side: Side) -> Intersection<'c, 'a, K, V, S> {
+        return Intersection::new(self.nodes.borrow(), other.nodes.borrow(), side);
+    }
+
+    /// Returns a lazy view over the pairs of this cloud whose keys the other cloud doesn't hold.
+    /// 
+    /// Both clouds stay borrowed until the view and every key it yielded are dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &3);
+    /// 
+    /// let only_here: Vec<(&str, &i32)> = cloud.difference(&other).map(|(key, value)| (*key, value)).collect();
+    /// assert_eq!(vec![("x", &1)], only_here);
+    /// ```
+    pub fn difference<'c>(&'c self, other: &'c DataCloud<'a, K, V, S>) -> Difference<'c, 'a, K, V, S> {
+        return Difference::new(self.nodes.borrow(), other.nodes.borrow());
+    }
+
+    /// Returns a lazy view over the pairs whose keys only one of the clouds holds: first the ones
+    /// only this cloud holds, then the ones only the other cloud holds.
+    /// 
+    /// Both clouds stay borrowed until the view and every key it yielded are dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &3);
+    /// other.insert("z", &4);
+    /// 
+    /// let unshared: Vec<(&str, &i32)> = cloud.symmetric_difference(&other).map(|(key, value)| (*key, value)).collect();
+    /// assert_eq!(vec![("x", &1), ("z", &4)], unshared);
+    /// ```
+    pub fn symmetric_difference<'c>(&'c self, other: &'c DataCloud<'a, K, V, S>) -> SymmetricDifference<'c, 'a, K, V, S> {
+        return SymmetricDifference::new(self.difference(other), other.difference(self));
+    }
+
+    /// Returns if every key of this cloud is also held by the other one. Values aren't compared.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("x", &2);
+    /// other.insert("y", &3);
+    /// 
+    /// assert!(cloud.is_subset(&other));
+    /// assert!(!other.is_subset(&cloud));
+    /// ```
+    pub fn is_subset(&self, other: &DataCloud<'a, K, V, S>) -> bool {
+        let (nodes, other) = (self.nodes.borrow(), other.nodes.borrow());
+        return nodes.len() <= other.len() && nodes.keys().all(|key| other.contains_key(key))
+    }
+
+    /// Returns if every key of the other cloud is also held by this one. Values aren't compared.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("x", &3);
+    /// 
+    /// assert!(cloud.is_superset(&other));
+    /// ```
+    pub fn is_superset(&self, other: &DataCloud<'a, K, V, S>) -> bool {
+        return other.is_subset(self)
+    }
+
+    /// Returns if the two clouds don't hold any key in common.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &2);
+    /// 
+    /// assert!(cloud.is_disjoint(&other));
+    /// ```
+    pub fn is_disjoint(&self, other: &DataCloud<'a, K, V, S>) -> bool {
+        let (nodes, other) = (self.nodes.borrow(), other.nodes.borrow());
+        let (smaller, larger) = if nodes.len() <= other.len() { (&nodes, &other) } else { (&other, &nodes) };
+        return !smaller.keys().any(|key| larger.contains_key(key))
+    }
+
+    /// Returns a new DataCloud holding the pairs whose keys both clouds hold, with values taken
+    /// from `side`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{DataCloud, Side};
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &3);
+    /// 
+    /// let shared = cloud.intersection_cloud(&other, Side::Left);
+    /// assert_eq!(vec![("y", &2)], shared.into_vec());
+    /// ```
+    pub fn intersection_cloud(&self, other: &DataCloud<'a, K, V, S>, side: Side) -> DataCloud<'a, K, V, S>
+    where
+        K: Clone,
+        S: Clone,
+    {
+        return self.collect_with_hasher(self.intersection(other, side));
+    }
+
+    /// Returns a new DataCloud holding the pairs of this cloud whose keys the other cloud doesn't hold.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &3);
+    /// 
+    /// let only_here = cloud.difference_cloud(&other);
+    /// assert_eq!(vec![("x", &1)], only_here.into_vec());
+    /// ```
+    pub fn difference_cloud(&self, other: &DataCloud<'a, K, V, S>) -> DataCloud<'a, K, V, S>
+    where
+        K: Clone,
+        S: Clone,
+    {
+        return self.collect_with_hasher(self.difference(other));
+    }
+
+    /// Returns a new DataCloud holding the pairs whose keys only one of the clouds holds.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// cloud.insert("y", &2);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &3);
+    /// other.insert("z", &4);
+    /// 
+    /// let unshared = cloud.symmetric_difference_cloud(&other);
+    /// assert_eq!(2, unshared.len());
+    /// assert!(unshared.contains_key("x") && unshared.contains_key("z"));
+    /// ```
+    pub fn symmetric_difference_cloud(&self, other: &DataCloud<'a, K, V, S>) -> DataCloud<'a, K, V, S>
+    where
+        K: Clone,
+        S: Clone,
+    {
+        return self.collect_with_hasher(self.symmetric_difference(other));
+    }
+
+    /// Collects borrowed pairs into a new DataCloud using a clone of this cloud's hasher.
+    fn collect_with_hasher<'c, I>(&self, pairs: I) -> DataCloud<'a, K, V, S>
+    where
+        K: Clone + 'c,
+        S: Clone,
+        I: Iterator<Item = (Ref<'c, K>, &'a V)>,
+    {
+        let mut nodes = HashMap::with_hasher(self.nodes.borrow().hasher().clone());
+        nodes.extend(pairs.map(|(key, value)| (key.clone(), value)));
+        return DataCloud::from_hashmap(nodes);
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> DataCloud<'a, K, V, S> {
+    /// Enters a callback-running operation, returning the guard that leaves it and the tick
+    /// of the queue its own mutations are stamped with.
+    /// 
+    /// The outermost operation borrows the cloud mutably first, so it panics if the cloud is
+    /// borrowed instead of leaving its mutations queued.
+    fn begin(&self) -> (Reentry<'_>, u64)
+    where
+        K: Clone,
+    {
+        if !self.is_deferring() {
+            drop(self.nodes.borrow_mut());
+        }
+        let reentry = Reentry::new(self);
+        return (reentry, self.queue().tick());
+    }
+
+    /// Returns if a callback-running operation is in progress, so mutations must be queued.
+    fn is_deferring(&self) -> bool {
+        return self.depth.get() > 0;
+    }
+
+    /// Returns the queue of a callback-running operation.
+    fn queue(&self) -> RefMut<'_, Pending<'a, K, V>> {
+        return RefMut::map(self.pending.borrow_mut(), |pending| {
+            pending.as_mut().expect("a callback-running operation has a queue")
+        });
+    }
+
+    /// Looks `key` up in `nodes` with the queued mutations applied.
+    fn lookup<Q>(&self, nodes: &HashMap<K, &'a V, S>, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let stored = nodes.get(key).copied();
+        return match &*self.pending.borrow() {
+            Some(pending) => pending.get(key, stored),
+            None => stored,
+        };
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: ?Sized, S: BuildHasher> Deferring for DataCloud<'a, K, V, S> {
+    fn enter(&self) {
+        self.pending.borrow_mut().get_or_insert_with(|| Pending::new(K::clone));
+        self.depth.set(self.depth.get() + 1);
+    }
+
+    fn leave(&self, discard: bool) {
+        let depth = self.depth.get() - 1;
+        self.depth.set(depth);
+        if depth == 0 {
+            self.settle(discard);
+        }
+    }
+
+    fn settle(&self, discard: bool) {
+        if self.is_deferring() {
+            return;
+        }
+        if discard {
+            self.pending.take();
+            return;
+        }
+        let Ok(mut nodes) = self.nodes.try_borrow_mut() else {
+            return;
+        };
+        if let Some(pending) = self.pending.take() {
+            pending.apply(&mut nodes);
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher + Default> DataCloud<'a, K, V, S> {
+    /// Returns a new DataCloud from the given vector of keys and values `Vec<(K, &'a V)>`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use cloudr::DataCloud;
+    ///
+    /// let mut vector = Vec::new();
+    /// vector.push((String::from("Banana"), &1));
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::from_vec(vector);
+    /// ```
+    pub fn from_vec<T: Into<Vec<(K, &'a V)>>>(vec: T) -> Self {
+        let mut hash = HashMap::default();
+
+        for (k, v) in vec.into() {
+            hash.insert(k, v);
+        }
+
+        Self::from_hashmap(hash)
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: ?Sized, S: BuildHasher + Clone> DataCloud<'a, K, V, S> {
+    /// Merges the DataCloud with another and returns the resulting one.
+    /// The other DataCloud will always have priority. So, if
+    /// there are two conflicting keys, the other one will always have priority.
+    /// Use [`merge_by`](DataCloud::merge_by) to pick another resolver.
+    /// 
+    /// # Examples
+    ///
+    /// ```
+    /// use cloudr::DataCloud;
+    ///
+    /// let cloud: DataCloud<String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// cloud.insert("y".to_string(), &24);
+    ///
+    /// let cloud2: DataCloud<String, i32> = DataCloud::new();
+    /// cloud2.insert("z".to_string(), &64);
+    /// 
+    /// assert!(cloud.merge(&cloud2).contains_key("z"));
+    /// ```
+    pub fn merge(&self, other: &DataCloud<'a, K, V, S>) -> DataCloud<'a, K, V, S> {
+        let new_cloud = DataCloud::with_hasher(self.nodes.borrow().hasher().clone());
+        for (key, value) in self.nodes.borrow().iter() {
+            new_cloud.insert(key.clone(), *value);
+        }
+
+        for (key, value) in other.nodes.borrow().iter() {
+            new_cloud.insert(key.clone(), *value);
+        }
 
         new_cloud
     }
+
+    /// Merges the DataCloud with other instances and returns the resulting one.
+    /// The last element of the vector `others` will always have priority. So, if
+    /// there are two conflicting keys, the last one will always have priority.
+    /// Use [`merge_all_by`](DataCloud::merge_all_by) to pick another resolver.
+    /// 
+    /// # Examples
+    ///
+    /// ```
+    /// use cloudr::DataCloud;
+    ///
+    /// let cloud: DataCloud<String, i32> = DataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// cloud.insert("y".to_string(), &24);
+    ///
+    /// let cloud2: DataCloud<String, i32> = DataCloud::new();
+    /// cloud2.insert("z".to_string(), &64);
+    /// 
+    /// assert!(cloud.merge_all(vec![&cloud2]).contains_key("z"));
+    /// ```
+    pub fn merge_all(&self, others: Vec<&DataCloud<'a, K, V, S>>) -> DataCloud<'a, K, V, S> {
+        let new_cloud = DataCloud::with_hasher(self.nodes.borrow().hasher().clone());
+        for (key, value) in self.nodes.borrow().iter() {
+            new_cloud.insert(key.clone(), *value);
+        }
+
+        for other in others {
+            for (key, value) in other.nodes.borrow().iter() {
+                new_cloud.insert(key.clone(), *value);
+            }
+        }
+
+        new_cloud
+    }
+
+    /// Merges the DataCloud with another one and returns the resulting one, resolving every
+    /// shared key with `resolver`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::merge::KeepLeft;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("x", &2);
+    /// other.insert("y", &3);
+    /// 
+    /// let (merged, report) = cloud.merge_by(&other, KeepLeft).unwrap();
+    /// 
+    /// assert_eq!(vec!["x"], report.conflicts);
+    /// assert_eq!(Some(&1), merged.get("x"));
+    /// assert_eq!(Some(&3), merged.get("y"));
+    /// ```
+    pub fn merge_by<R>(&self, other: &DataCloud<'a, K, V, S>, resolver: R) -> MergeResult<DataCloud<'a, K, V, S>, K>
+    where
+        R: Resolver<K, &'a V>,
+    {
+        return self.merge_all_by(vec![other], resolver);
+    }
+
+    /// Merges the DataCloud with other instances from left to right and returns the resulting one,
+    /// resolving every shared key with `resolver`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::merge::ErrorOnConflict;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &2);
+    /// 
+    /// let last: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// last.insert("x", &3);
+    /// 
+    /// let refused = cloud.merge_all_by(vec![&other, &last], ErrorOnConflict).unwrap_err();
+    /// assert_eq!("x", refused.key);
+    /// ```
+    pub fn merge_all_by<R>(&self, others: Vec<&DataCloud<'a, K, V, S>>, mut resolver: R) -> MergeResult<DataCloud<'a, K, V, S>, K>
+    where
+        R: Resolver<K, &'a V>,
+    {
+        let mut nodes = self.nodes.borrow().clone();
+        let mut report = MergeReport::new();
+        for other in others {
+            let pairs = other.nodes.borrow().iter().map(|(key, &value)| (key.clone(), value)).collect::<Vec<_>>();
+            resolve_into(&mut nodes, pairs, &mut resolver, &mut report)?;
+        }
+        return Ok((DataCloud::from_hashmap(nodes), report));
+    }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Index<&K> for DataCloud<'a, K, V> {
+impl<'a, K, Q, V, S> Index<&Q> for DataCloud<'a, K, V, S>
+where
+    K: PartialEq + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    V: ?Sized,
+    S: BuildHasher,
+{
     type Output = V;
 
-    fn index(&self, index: &K) -> &Self::Output {
+    fn index(&self, index: &Q) -> &Self::Output {
         return self.get(index).unwrap()
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> IndexMut<&K> for DataCloud<'a, K, V> {
-    fn index_mut(&mut self, index: &K) -> &mut Self::Output {
-        return self.get_mut(index).unwrap()
-    }
-}
-
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Extend<(K, &'a V)> for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> Extend<(K, &'a V)> for DataCloud<'a, K, V, S> {
     fn extend<T: IntoIterator<Item = (K, &'a V)>>(&mut self, iter: T) {
         let mut nodes = self.nodes.borrow_mut();
-        let mut iter = iter.into_iter();
-        while let Some((k, v)) = iter.next() {
+        for (k, v) in iter {
             nodes.insert(k, v);
         }
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> FromIterator<(K, &'a V)> for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher + Default> FromIterator<(K, &'a V)> for DataCloud<'a, K, V, S> {
     fn from_iter<T: IntoIterator<Item = (K, &'a V)>>(iter: T) -> Self {
         return Self::from_vec(iter.into_iter().collect::<Vec<_>>())
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Into<Vec<(K, &'a V)>> for DataCloud<'a, K, V> {
-    fn into(self) -> Vec<(K, &'a V)> {
-        return self.into_vec()
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> From<DataCloud<'a, K, V, S>> for Vec<(K, &'a V)> {
+    fn from(value: DataCloud<'a, K, V, S>) -> Self {
+        return value.into_vec()
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Into<VecDeque<(K, &'a V)>> for DataCloud<'a, K, V> {
-    fn into(self) -> VecDeque<(K, &'a V)> {
-        return self.into_vecdeque()
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> From<DataCloud<'a, K, V, S>> for VecDeque<(K, &'a V)> {
+    fn from(value: DataCloud<'a, K, V, S>) -> Self {
+        return value.into_vecdeque()
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> From<FxHashMap<K, &'a V>> for DataCloud<'a, K, V> {
-    fn from(value: FxHashMap<K, &'a V>) -> Self {
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> From<HashMap<K, &'a V, S>> for DataCloud<'a, K, V, S> {
+    fn from(value: HashMap<K, &'a V, S>) -> Self {
         return Self::from_hashmap(value)
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash + Clone, V: PartialEq + Eq> Clone for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: ?Sized, S: Clone> Clone for DataCloud<'a, K, V, S> {
     fn clone(&self) -> Self {
-        return DataCloud {
-            nodes: self.nodes.clone(),
-        }
+        return DataCloud::from_hashmap(self.nodes.borrow().clone());
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash + Clone + Debug, V: PartialEq + Eq + Debug> Debug for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash + Clone + Debug, V: Debug + ?Sized, S> Debug for DataCloud<'a, K, V, S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut output = String::from("Cloud {\n");
         for (k, v) in self.nodes.borrow().iter() {
             output.push_str(&format!("    ({:?}: {:?}), \n", k, v));
         }
         output.remove(output.len() - 1);
-        output.push_str("}");
+        output.push('}');
         write!(f, "{}", output)
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash + Clone + Display, V: PartialEq + Eq + Display> Display for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash + Clone + Display, V: Display + ?Sized, S> Display for DataCloud<'a, K, V, S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut output = String::from("{\n");
         for (k, v) in self.nodes.borrow().iter() {
             output.push_str(&format!("    ({}: {}), \n", k, v));
         }
         output.remove(output.len() - 1);
-        output.push_str("}");
+        output.push('}');
         write!(f, "{}", output)
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> PartialEq for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + ?Sized, S: BuildHasher> PartialEq for DataCloud<'a, K, V, S> {
+    /// Two clouds are equal when they hold the same keys, and the values referenced under each key
+    /// compare equal. Iteration order doesn't matter.
     fn eq(&self, other: &Self) -> bool {
-        return self.nodes.borrow().iter().zip(other.nodes.borrow().iter()).all(|(kv1, kv2)| kv1 == kv2)
+        return *self.nodes.borrow() == *other.nodes.borrow()
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> IntoIterator for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> IntoIterator for DataCloud<'a, K, V, S> {
     type IntoIter = IntoIter<K, &'a V>;
     type Item = (K, &'a V);
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter::new(self.nodes.into_inner().into_iter().collect())
+        IntoIter::new(self.nodes.into_inner())
+    }
+}
+
+impl<'c, 'a, K: PartialEq + Eq + Hash, V: ?Sized, S> IntoIterator for &'c DataCloud<'a, K, V, S> {
+    type IntoIter = Iter<'c, 'a, K, V>;
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self.nodes.borrow())
+    }
+}
+
+impl<'c, 'a, K: PartialEq + Eq + Hash, V: ?Sized, S> IntoIterator for &'c mut DataCloud<'a, K, V, S> {
+    type IntoIter = IterMut<'c, 'a, K, V>;
+    type Item = (&'c K, &'c mut &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        IterMut::new(self.nodes.get_mut())
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash + PartialOrd, V: PartialEq + Eq + PartialOrd> PartialOrd for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash + Ord, V: PartialOrd + ?Sized, S: BuildHasher> PartialOrd for DataCloud<'a, K, V, S> {
+    /// Compares two clouds as the sequences of their pairs sorted by key, the way two
+    /// `BTreeMap`s compare. Iteration order doesn't matter.
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        return self.nodes.borrow().iter().zip(other.nodes.borrow().iter())
-            .map(|(kv1, kv2)| kv1.partial_cmp(&kv2))
-            .fold(Some(std::cmp::Ordering::Equal), |acc, ord| Some(acc?.then(ord?)));
+        return sorted_pairs(&self.nodes.borrow()).partial_cmp(&sorted_pairs(&other.nodes.borrow()));
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Ord, V: Ord + ?Sized, S: BuildHasher> Ord for DataCloud<'a, K, V, S> {
+    /// Compares two clouds as the sequences of their pairs sorted by key, the way two
+    /// `BTreeMap`s compare. Iteration order doesn't matter.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        return sorted_pairs(&self.nodes.borrow()).cmp(&sorted_pairs(&other.nodes.borrow()));
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Eq for DataCloud<'a, K, V> {}
+/// Returns the pairs of a map sorted by key.
+fn sorted_pairs<'m, 'a, K: Ord, V: ?Sized, S>(nodes: &'m HashMap<K, &'a V, S>) -> Vec<(&'m K, &'a V)> {
+    let mut pairs: Vec<(&'m K, &'a V)> = nodes.iter().map(|(key, &value)| (key, value)).collect();
+    pairs.sort_unstable_by_key(|&(key, _)| key);
+    return pairs;
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: Eq + ?Sized, S: BuildHasher> Eq for DataCloud<'a, K, V, S> {}
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash> Hash for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: Hash + ?Sized, S> Hash for DataCloud<'a, K, V, S> {
+    /// Hashes the cloud independently of its iteration order, so equal clouds hash equally.
+    /// 
+    /// Each pair is hashed on its own with a fixed-key `DefaultHasher`, and the results are
+    /// summed, which doesn't depend on the order the pairs are visited in.
     fn hash<H: Hasher>(&self, state: &mut H) {
-        // Hash the length of the map
-        self.nodes.borrow().len().hash(state);
-        // Hash each key-value pair in the map
-        for (k, v) in self.nodes.borrow().iter() {
-            k.hash(state);
-            v.hash(state);
-        }
+        let nodes = self.nodes.borrow();
+        let combined = nodes.iter().fold(0u64, |acc, pair| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            pair.hash(&mut hasher);
+            return acc.wrapping_add(hasher.finish());
+        });
+        nodes.len().hash(state);
+        combined.hash(state);
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Default for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: Default> Default for DataCloud<'a, K, V, S> {
     fn default() -> Self {
-        return Self::new();
+        return Self::with_hasher(S::default());
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash + Clone, V: PartialEq + Eq + Clone, S: BuildHasher + Default> IntoOwned<K, V, S> for DataCloud<'a, K, V> {
-    fn into_owned(&self) -> HashMap<K, V, S> {
-        let mut new_map = HashMap::with_hasher(S::default());
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: Clone, S, T: BuildHasher + Default> IntoOwned<K, V, T> for DataCloud<'a, K, V, S> {
+    fn into_owned(&self) -> HashMap<K, V, T> {
+        let mut new_map = HashMap::with_hasher(T::default());
 
         for (k, v) in self.nodes.borrow().iter() {
-            new_map.insert(k.clone(), v.clone().clone());
+            new_map.insert(k.clone(), (*v).clone());
         }
 
         new_map
     }
 }
 
-impl<'a, K: PartialEq + Eq + Hash + Clone, V: PartialEq + Eq> CombineWith for DataCloud<'a, K, V> {
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: ?Sized, S: BuildHasher + Clone> CombineWith for DataCloud<'a, K, V, S> {
+    type Key = K;
+    type Ref = &'a V;
+
     /// Enables the DataCloud to combine with other instances of the same type
+    /// 
+    /// On conflicting keys, this DataCloud always has priority. Use `combine_with_by` to pick
+    /// another resolver.
+    /// 
     /// # Examples
     /// ```
     /// use cloudr::DataCloud;
@@ -914,10 +2274,9 @@ impl<'a, K: PartialEq + Eq + Hash + Clone, V: PartialEq + Eq> CombineWith for Da
     fn combine_with(&self, others: Vec<Self>) -> Self
         where
             Self: Sized {
-        let new_cloud = DataCloud::<K, V, FxBuildHasher>::new();
-        for cloud in others.into_iter() {
-            let mut iter = cloud.into_iter();
-            while let Some((key, val)) = iter.next() {
+        let new_cloud = DataCloud::with_hasher(self.nodes.borrow().hasher().clone());
+        for cloud in others {
+            for (key, val) in cloud {
                 new_cloud.insert(key, val);
             }
         }
@@ -926,9 +2285,394 @@ impl<'a, K: PartialEq + Eq + Hash + Clone, V: PartialEq + Eq> CombineWith for Da
         }
         new_cloud
     }
+
+    /// Combines the DataCloud with other instances from left to right, resolving every shared
+    /// key with `resolver`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::CombineWith;
+    /// use cloudr::merge::KeepRight;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// let cloud2: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud2.insert("x", &2);
+    /// 
+    /// let (combined, report) = cloud.combine_with_by(vec![cloud2], KeepRight).unwrap();
+    /// 
+    /// assert_eq!(vec!["x"], report.conflicts);
+    /// assert_eq!(Some(&2), combined.get("x"));
+    /// ```
+    fn combine_with_by<R>(&self, others: Vec<Self>, mut resolver: R) -> MergeResult<Self, K>
+    where
+        R: Resolver<K, &'a V>,
+    {
+        let mut nodes = self.nodes.borrow().clone();
+        let mut report = MergeReport::new();
+        for other in others {
+            resolve_into(&mut nodes, other.nodes.into_inner(), &mut resolver, &mut report)?;
+        }
+        return Ok((DataCloud::from_hashmap(nodes), report));
+    }
+}
+
+/// A companion to [`DataCloud`] that stores mutable references without moving the values.
+/// 
+/// Mutable access is only ever handed out through [`CloudRefMut`] guards, so two live
+/// mutable borrows of the same entry can never exist at once.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::MutDataCloud;
+/// 
+/// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+/// let mut x = 42;
+/// cloud.insert("x".to_string(), &mut x);
+/// 
+/// *cloud.get_mut("x").unwrap() += 1;
+/// 
+/// assert_eq!(43, *cloud.get("x").unwrap());
+/// ```
+pub struct MutDataCloud<'a, K: PartialEq + Eq + Hash, V: ?Sized, S = DefaultHashBuilder> {
+    nodes: RefCell<HashMap<K, RefCell<&'a mut V>, S>>,
+}
+
+/// A shared borrow guard of a value stored in a [`MutDataCloud`].
+pub struct CloudRef<'b, V: ?Sized> {
+    value: Ref<'b, V>,
+    _nodes: Ref<'b, ()>,
+}
+
+/// A mutable borrow guard of a value stored in a [`MutDataCloud`].
+pub struct CloudRefMut<'b, V: ?Sized> {
+    value: RefMut<'b, V>,
+    _nodes: Ref<'b, ()>,
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized> MutDataCloud<'a, K, V> {
+    /// Returns a new instance of a MutDataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// ```
+    pub fn new() -> MutDataCloud<'a, K, V> {
+        return MutDataCloud::with_hasher(Default::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> MutDataCloud<'a, K, V, S> {
+    /// Returns a new instance of a MutDataCloud which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32, RandomState> = MutDataCloud::with_hasher(RandomState::new());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> MutDataCloud<'a, K, V, S> {
+        return MutDataCloud {
+            nodes: RefCell::new(HashMap::with_hasher(hash_builder)),
+        }
+    }
+
+    /// Returns a new instance of a MutDataCloud with room for at least `capacity` pairs, which
+    /// will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32, RandomState> = MutDataCloud::with_capacity_and_hasher(16, RandomState::new());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> MutDataCloud<'a, K, V, S> {
+        return MutDataCloud {
+            nodes: RefCell::new(HashMap::with_capacity_and_hasher(capacity, hash_builder)),
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> MutDataCloud<'a, K, V, S> {
+    /// Inserts a new key into the cloud, returning the mutable reference it replaced.
+    /// 
+    /// # Panics
+    /// Panics if any guard handed out by the cloud is still alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut y = 3;
+    /// let inserted_before: Option<&mut i32> = cloud.insert("y".to_string(), &mut y);
+    /// 
+    /// assert!(inserted_before.is_none());
+    /// ```
+    pub fn insert(&self, key: K, value: &'a mut V) -> Option<&'a mut V> {
+        return self.nodes.borrow_mut().insert(key, RefCell::new(value)).map(RefCell::into_inner)
+    }
+
+    /// Gets a shared borrow guard of the value stored in the cloud.
+    /// 
+    /// # Panics
+    /// Panics if the value is currently borrowed mutably.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut y = 3;
+    /// cloud.insert("y".to_string(), &mut y);
+    /// 
+    /// assert_eq!(3, *cloud.get("y").unwrap());
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<CloudRef<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let nodes = self.nodes.borrow();
+        let cell: *const RefCell<&'a mut V> = nodes.get(key)?;
+        // SAFETY: the shared borrow of the map travels with the guard, so the entry can
+        // neither be removed nor moved by a rehash while the guard is alive.
+        let value = Ref::map(unsafe { &*cell }.borrow(), |value| &**value);
+        return Some(CloudRef {
+            value,
+            _nodes: Ref::map(nodes, |_| &()),
+        })
+    }
+
+    /// Gets a mutable borrow guard of the value stored in the cloud.
+    /// 
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut y = 3;
+    /// cloud.insert("y".to_string(), &mut y);
+    /// 
+    /// *cloud.get_mut("y").unwrap() = 4;
+    /// 
+    /// assert_eq!(4, *cloud.get("y").unwrap());
+    /// ```
+    pub fn get_mut<Q>(&self, key: &Q) -> Option<CloudRefMut<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let nodes = self.nodes.borrow();
+        let cell: *const RefCell<&'a mut V> = nodes.get(key)?;
+        // SAFETY: the shared borrow of the map travels with the guard, so the entry can
+        // neither be removed nor moved by a rehash while the guard is alive.
+        let value = RefMut::map(unsafe { &*cell }.borrow_mut(), |value| &mut **value);
+        return Some(CloudRefMut {
+            value,
+            _nodes: Ref::map(nodes, |_| &()),
+        })
+    }
+
+    /// Gets multiple mutable borrow guards at a time.
+    /// 
+    /// # Panics
+    /// Panics if the same key is requested twice or one of the values is already borrowed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut x = 42;
+    /// let mut y = 24;
+    /// cloud.insert("x".to_string(), &mut x);
+    /// cloud.insert("y".to_string(), &mut y);
+    /// 
+    /// let x_string = "x".to_string();
+    /// let y_string = "y".to_string();
+    /// 
+    /// if let [Some(x_guard), Some(y_guard)] = &mut cloud.get_mut_all(vec![&x_string, &y_string])[..] {
+    ///     std::mem::swap(&mut **x_guard, &mut **y_guard);
+    /// }
+    /// 
+    /// assert_eq!(24, *cloud.get(&x_string).unwrap());
+    /// ```
+    pub fn get_mut_all<Q>(&self, keys: Vec<&Q>) -> Vec<Option<CloudRefMut<'_, V>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut out = Vec::new();
+        for key in keys {
+            out.push(self.get_mut(key));
+        }
+        out
+    }
+
+    /// Removes the mutable reference stored in the cloud and returns it if it exists.
+    /// 
+    /// # Panics
+    /// Panics if any guard handed out by the cloud is still alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut y = 3;
+    /// cloud.insert("y".to_string(), &mut y);
+    /// 
+    /// let y_ref: &mut i32 = cloud.remove("y").unwrap();
+    /// *y_ref = 5;
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Option<&'a mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow_mut().remove(key).map(RefCell::into_inner)
+    }
+
+    /// Returns if the cloud contains a reference indexed by this key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut x = 63;
+    /// cloud.insert("x".to_string(), &mut x);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow().contains_key(key)
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.borrow().is_empty();
+    }
+
+    /// Clears the `MutDataCloud`'s key-value pairs.
+    /// 
+    /// # Panics
+    /// Panics if any guard handed out by the cloud is still alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, usize, i128> = MutDataCloud::new();
+    /// let mut v = 54;
+    /// cloud.insert(3, &mut v);
+    /// 
+    /// cloud.clear();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.nodes.borrow_mut().clear();
+    }
+
+    /// Consumes the MutDataCloud and returns a vector of tuples containing `(K, &'a mut V)`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::MutDataCloud;
+    /// 
+    /// let cloud: MutDataCloud<'_, String, i32> = MutDataCloud::new();
+    /// let mut y = 3;
+    /// cloud.insert("y".to_string(), &mut y);
+    /// 
+    /// let vec: Vec<(String, &mut i32)> = cloud.into_vec();
+    /// assert_eq!(vec, vec![("y".to_string(), &mut 3)]);
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a mut V)> {
+        return self.nodes.into_inner().into_iter().map(|(k, v)| (k, v.into_inner())).collect::<Vec<_>>();
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> IntoIterator for MutDataCloud<'a, K, V, S> {
+    type IntoIter = MutIntoIter<'a, K, V>;
+    type Item = (K, &'a mut V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        MutIntoIter::new(self.nodes.into_inner())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: Default> Default for MutDataCloud<'a, K, V, S> {
+    fn default() -> Self {
+        return Self::with_hasher(S::default());
+    }
+}
+
+impl<'b, V: ?Sized> Deref for CloudRef<'b, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.value
+    }
+}
+
+impl<'b, V: Debug + ?Sized> Debug for CloudRef<'b, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return Debug::fmt(&*self.value, f)
+    }
+}
+
+impl<'b, V: Display + ?Sized> Display for CloudRef<'b, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return Display::fmt(&*self.value, f)
+    }
+}
+
+impl<'b, V: ?Sized> Deref for CloudRefMut<'b, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.value
+    }
+}
+
+impl<'b, V: ?Sized> DerefMut for CloudRefMut<'b, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.value
+    }
 }
 
-unsafe impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> Send for DataCloud<'a, K, V> {}
-unsafe impl<'a, K: PartialEq + Eq + Hash + Send + Sync, V: PartialEq + Eq + Send + Sync> Sync for DataCloud<'a, K, V> {}
+impl<'b, V: Debug + ?Sized> Debug for CloudRefMut<'b, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return Debug::fmt(&*self.value, f)
+    }
+}
 
-impl<'a, K: PartialEq + Eq + Hash, V: PartialEq + Eq> !Copy for DataCloud<'a, K, V> {}
+impl<'b, V: Display + ?Sized> Display for CloudRefMut<'b, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return Display::fmt(&*self.value, f)
+    }
+}
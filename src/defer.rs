@@ -0,0 +1,178 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash}
+};
+
+use crate::DefaultHashBuilder;
+
+/// The mutations made while the cloud was running a callback, waiting for the outermost
+/// operation to finish.
+/// 
+/// Every touched key is stored with its new reference, or `None` if it was removed, so lookups
+/// made in the meantime see the mutations. Each change is stamped with a tick, which lets an
+/// operation tell the changes its callbacks made from older ones.
+pub(crate) struct Pending<'a, K, V: ?Sized> {
+    changes: HashMap<K, (u64, Option<&'a V>), DefaultHashBuilder>,
+    /// The tick of the last clear, which hides every key of the cloud not touched since.
+    cleared: Option<u64>,
+    tick: u64,
+    clone_key: fn(&K) -> K,
+}
+
+impl<'a, K: Hash + Eq, V: ?Sized> Pending<'a, K, V> {
+    pub(crate) fn new(clone_key: fn(&K) -> K) -> Pending<'a, K, V> {
+        return Pending {
+            changes: HashMap::default(),
+            cleared: None,
+            tick: 0,
+            clone_key,
+        }
+    }
+
+    /// Returns the tick the next change will get, which marks the start of an operation.
+    pub(crate) fn tick(&self) -> u64 {
+        return self.tick;
+    }
+
+    /// Returns the reference of `key` with the queued changes applied, given the reference
+    /// `stored` in the cloud.
+    pub(crate) fn get<Q>(&self, key: &Q, stored: Option<&'a V>) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if let Some(&(_, value)) = self.changes.get(key) {
+            return value;
+        }
+        return if self.cleared.is_some() { None } else { stored };
+    }
+
+    /// Queues `value` as the new reference of `key`.
+    pub(crate) fn insert(&mut self, key: K, value: &'a V) {
+        self.changes.insert(key, (self.tick, Some(value)));
+        self.tick += 1;
+    }
+
+    /// Queues the removal of `key` and returns its reference with the queued changes applied,
+    /// given the pair `stored` in the cloud. The stored key is cloned if the queue doesn't hold
+    /// the key yet.
+    pub(crate) fn remove<Q>(&mut self, key: &Q, stored: Option<(&K, &'a V)>) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let value = match self.changes.get_mut(key) {
+            Some(change) => {
+                let value = change.1?;
+                *change = (self.tick, None);
+                value
+            }
+            None if self.cleared.is_some() => return None,
+            None => {
+                let (stored, value) = stored?;
+                self.changes.insert((self.clone_key)(stored), (self.tick, None));
+                value
+            }
+        };
+        self.tick += 1;
+        return Some(value);
+    }
+
+    /// Queues the removal of every key.
+    pub(crate) fn clear(&mut self) {
+        self.changes.clear();
+        self.cleared = Some(self.tick);
+        self.tick += 1;
+    }
+
+    /// Queues a change an operation that started at `start` made itself. Its callbacks ran
+    /// first but their changes win, so the change is dropped if they touched the key.
+    pub(crate) fn set_since(&mut self, start: u64, key: K, value: Option<&'a V>) {
+        if self.cleared.is_some_and(|cleared| cleared >= start) {
+            return;
+        }
+        if self.changes.get(&key).is_some_and(|&(tick, _)| tick >= start) {
+            return;
+        }
+        self.changes.insert(key, (start, value));
+    }
+
+    /// Applies the queued changes to the cloud's map.
+    pub(crate) fn apply<S: BuildHasher>(self, nodes: &mut HashMap<K, &'a V, S>) {
+        if self.cleared.is_some() {
+            nodes.clear();
+        }
+        for (key, (_, value)) in self.changes {
+            match value {
+                Some(value) => nodes.insert(key, value),
+                None => nodes.remove(&key),
+            };
+        }
+    }
+}
+
+/// A cloud that can run callbacks that call back into it.
+pub(crate) trait Deferring {
+    /// Enters one level of callbacks, so mutations made through the cloud are queued.
+    fn enter(&self);
+
+    /// Leaves one level of callbacks, and settles the queue once the last one is left.
+    fn leave(&self, discard: bool);
+
+    /// Applies the queued mutations if no callback is running and the cloud isn't borrowed
+    /// anymore, or drops them if `discard` is set.
+    fn settle(&self, discard: bool);
+}
+
+/// Keeps a cloud in the callback-running state until it is dropped.
+/// 
+/// Declare it before any borrow guard of the same cloud, so it drops after them and the queue
+/// can be applied.
+/// 
+/// The queued mutations are discarded only if a panic started during the operation. An operation
+/// run from a `Drop` impl while the thread is already unwinding still applies them.
+pub(crate) struct Reentry<'c> {
+    cloud: &'c dyn Deferring,
+    panicking: bool,
+}
+
+impl<'c> Reentry<'c> {
+    pub(crate) fn new(cloud: &'c dyn Deferring) -> Reentry<'c> {
+        cloud.enter();
+        return Reentry { cloud, panicking: std::thread::panicking() };
+    }
+}
+
+impl Drop for Reentry<'_> {
+    fn drop(&mut self) {
+        self.cloud.leave(std::thread::panicking() && !self.panicking);
+    }
+}
+
+/// Settles the queue of a cloud when dropped, for iterators that run callbacks lazily while
+/// holding a borrow of the cloud.
+/// 
+/// The callback-running state only lasts for each callback, through [`Settle::reentry`], so a
+/// leaked iterator doesn't leave the cloud queueing mutations.
+pub(crate) struct Settle<'c> {
+    cloud: &'c dyn Deferring,
+    panicking: bool,
+}
+
+impl<'c> Settle<'c> {
+    pub(crate) fn new(cloud: &'c dyn Deferring) -> Settle<'c> {
+        return Settle { cloud, panicking: std::thread::panicking() };
+    }
+
+    /// Enters the callback-running state until the returned guard is dropped.
+    pub(crate) fn reentry(&self) -> Reentry<'c> {
+        return Reentry::new(self.cloud);
+    }
+}
+
+impl Drop for Settle<'_> {
+    fn drop(&mut self) {
+        self.cloud.settle(std::thread::panicking() && !self.panicking);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    fmt::Display,
+    hash::{BuildHasher, Hash}
+};
+
+use crate::{alias, DataCloud};
+
+/// How [`DataCloud::diff_by`] decides that a key held by both clouds has changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    /// The key changed when the referenced values aren't equal.
+    #[default]
+    Value,
+    /// The key changed when the references point to different addresses, or to slices of
+    /// different lengths, even if the values they point to are equal.
+    Address,
+}
+
+/// A key whose reference differs between the two sides of a [`CloudDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changed<'a, K, V: ?Sized> {
+    /// The key held by both clouds.
+    pub key: K,
+    /// The reference in the cloud the diff starts from.
+    pub old: &'a V,
+    /// The reference in the cloud the diff leads to.
+    pub new: &'a V,
+}
+
+/// The differences between two clouds, as returned by [`DataCloud::diff`].
+/// 
+/// Applying the diff to the cloud it starts from turns it into the cloud it leads to, and
+/// [`invert`](CloudDiff::invert) gives the diff that leads back. Entries come in arbitrary order.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::DataCloud;
+/// 
+/// let before: DataCloud<'_, &str, i32> = DataCloud::new();
+/// before.insert("kept", &1);
+/// before.insert("dropped", &2);
+/// 
+/// let after: DataCloud<'_, &str, i32> = DataCloud::new();
+/// after.insert("kept", &10);
+/// after.insert("new", &3);
+/// 
+/// let diff = before.diff(&after);
+/// assert_eq!(vec![("new", &3)], diff.added);
+/// assert_eq!(vec![("dropped", &2)], diff.removed);
+/// assert_eq!("kept", diff.changed[0].key);
+/// 
+/// diff.apply(&before);
+/// assert_eq!(before, after);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudDiff<'a, K, V: ?Sized> {
+    /// The pairs only the cloud the diff leads to holds.
+    pub added: Vec<(K, &'a V)>,
+    /// The pairs only the cloud the diff starts from holds.
+    pub removed: Vec<(K, &'a V)>,
+    /// The keys both clouds hold with different references.
+    pub changed: Vec<Changed<'a, K, V>>,
+}
+
+impl<'a, K, V: ?Sized> CloudDiff<'a, K, V> {
+    /// Returns if the two clouds didn't differ.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// 
+    /// assert!(cloud.diff(&cloud).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty();
+    }
+
+    /// Returns the keys the diff touches, in the order `added`, `removed`, `changed`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let before: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// before.insert("x", &1);
+    /// let after: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// 
+    /// assert_eq!(vec![&"x"], before.diff(&after).keys().collect::<Vec<_>>());
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        return self.added.iter().map(|(key, _)| key)
+            .chain(self.removed.iter().map(|(key, _)| key))
+            .chain(self.changed.iter().map(|changed| &changed.key));
+    }
+
+    /// Returns the diff that leads back from the cloud this one leads to.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let before: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// before.insert("x", &1);
+    /// 
+    /// let after: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// after.insert("x", &2);
+    /// after.insert("y", &3);
+    /// 
+    /// let diff = before.diff(&after);
+    /// diff.apply(&before);
+    /// diff.invert().apply(&before);
+    /// 
+    /// assert_eq!(vec![("x", &1)], before.into_vec());
+    /// ```
+    pub fn invert(self) -> CloudDiff<'a, K, V> {
+        return CloudDiff {
+            added: self.removed,
+            removed: self.added,
+            changed: self.changed.into_iter().map(|Changed { key, old, new }| Changed { key, old: new, new: old }).collect(),
+        }
+    }
+
+    /// Replays the diff onto a cloud: removes the removed keys, and inserts the added and changed
+    /// ones with their new references.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let before: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// let after: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// after.insert("x", &1);
+    /// 
+    /// let replica: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// before.diff(&after).apply(&replica);
+    /// 
+    /// assert_eq!(Some(&1), replica.get("x"));
+    /// ```
+    pub fn apply<S>(&self, cloud: &DataCloud<'a, K, V, S>)
+    where
+        K: PartialEq + Eq + Hash + Clone,
+        S: BuildHasher,
+    {
+        for (key, _) in &self.removed {
+            cloud.remove(key);
+        }
+        for (key, value) in &self.added {
+            cloud.insert(key.clone(), *value);
+        }
+        for Changed { key, new, .. } in &self.changed {
+            cloud.insert(key.clone(), *new);
+        }
+    }
+}
+
+impl<'a, K: Display, V: Display + ?Sized> Display for CloudDiff<'a, K, V> {
+    /// Writes one line per entry: `+ key: value` for added keys, `- key: value` for removed ones,
+    /// and `~ key: old -> new` for changed ones.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.added {
+            writeln!(f, "+ {}: {}", key, value)?;
+        }
+        for (key, value) in &self.removed {
+            writeln!(f, "- {}: {}", key, value)?;
+        }
+        for Changed { key, old, new } in &self.changed {
+            writeln!(f, "~ {}: {} -> {}", key, old, new)?;
+        }
+        return Ok(());
+    }
+}
+
+pub(crate) fn diff<'a, K, V, S>(from: &DataCloud<'a, K, V, S>, to: &DataCloud<'a, K, V, S>, comparison: Comparison) -> CloudDiff<'a, K, V>
+where
+    K: PartialEq + Eq + Hash + Clone,
+    V: PartialEq + ?Sized,
+    S: BuildHasher,
+{
+    let (from, to) = (from.nodes.borrow(), to.nodes.borrow());
+    let mut diff = CloudDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (key, &old) in from.iter() {
+        match to.get(key) {
+            None => diff.removed.push((key.clone(), old)),
+            Some(&new) => {
+                let differs = match comparison {
+                    Comparison::Value => old != new,
+                    Comparison::Address => !alias::same_referent(old, new),
+                };
+                if differs {
+                    diff.changed.push(Changed { key: key.clone(), old, new });
+                }
+            }
+        }
+    }
+    for (key, &new) in to.iter() {
+        if !from.contains_key(key) {
+            diff.added.push((key.clone(), new));
+        }
+    }
+    return diff;
+}
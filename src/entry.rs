@@ -0,0 +1,288 @@
+use std::{
+    cell::RefMut,
+    collections::{hash_map, HashMap},
+    hash::{BuildHasher, Hash}
+};
+
+/// A view into a single entry of a [`DataCloud`](crate::DataCloud), which may either be vacant or occupied.
+/// 
+/// The entry keeps the cloud mutably borrowed until it is consumed or dropped.
+pub enum Entry<'c, 'a, K, V: ?Sized, S> {
+    Occupied(OccupiedEntry<'c, 'a, K, V, S>),
+    Vacant(VacantEntry<'c, 'a, K, V, S>),
+}
+
+/// A view into an occupied entry of a [`DataCloud`](crate::DataCloud).
+pub struct OccupiedEntry<'c, 'a, K, V: ?Sized, S> {
+    entry: hash_map::OccupiedEntry<'c, K, &'a V>,
+    _nodes: RefMut<'c, HashMap<K, &'a V, S>>,
+}
+
+/// A view into a vacant entry of a [`DataCloud`](crate::DataCloud).
+pub struct VacantEntry<'c, 'a, K, V: ?Sized, S> {
+    entry: hash_map::VacantEntry<'c, K, &'a V>,
+    _nodes: RefMut<'c, HashMap<K, &'a V, S>>,
+}
+
+impl<'c, 'a, K: Eq + Hash, V: ?Sized, S: BuildHasher> Entry<'c, 'a, K, V, S> {
+    pub(crate) fn new(mut nodes: RefMut<'c, HashMap<K, &'a V, S>>, key: K) -> Entry<'c, 'a, K, V, S> {
+        let map: *mut HashMap<K, &'a V, S> = &mut *nodes;
+        // SAFETY: the map lives inside the cloud's `RefCell`, not inside the guard, so it does
+        // not move with it. The guard is stored next to the entry and dropped after it, which
+        // keeps the `RefCell` mutably borrowed for as long as the entry can touch the map.
+        return match unsafe { &mut *map }.entry(key) {
+            hash_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry { entry, _nodes: nodes }),
+            hash_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry { entry, _nodes: nodes }),
+        }
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized, S> Entry<'c, 'a, K, V, S> {
+    /// Returns a reference to this entry's key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// 
+    /// assert_eq!("x", cloud.entry("x".to_string()).key());
+    /// ```
+    pub fn key(&self) -> &K {
+        return match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a reference is stored in the entry by inserting `default` if it is vacant,
+    /// and returns the stored reference.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// assert_eq!(&42, cloud.entry("x".to_string()).or_insert(&x));
+    /// assert_eq!(&42, cloud.entry("x".to_string()).or_insert(&y));
+    /// ```
+    pub fn or_insert(self, default: &'a V) -> &'a V {
+        return match self {
+            Entry::Occupied(entry) => entry.get(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a reference is stored in the entry by inserting the result of `default` if it is vacant,
+    /// and returns the stored reference.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// 
+    /// assert_eq!(&42, cloud.entry("x".to_string()).or_insert_with(|| &x));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> &'a V>(self, default: F) -> &'a V {
+        return match self {
+            Entry::Occupied(entry) => entry.get(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a reference is stored in the entry by inserting the result of `default` if it is vacant,
+    /// and returns the stored reference. The closure receives the entry's key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, usize, usize> = DataCloud::new();
+    /// let values = [10, 11, 12];
+    /// 
+    /// assert_eq!(&12, cloud.entry(2).or_insert_with_key(|key| &values[*key]));
+    /// ```
+    pub fn or_insert_with_key<F: FnOnce(&K) -> &'a V>(self, default: F) -> &'a V {
+        return match self {
+            Entry::Occupied(entry) => entry.get(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Gives access to the stored reference of an occupied entry before any potential inserts.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// cloud.entry("x".to_string()).and_modify(|value| *value = &y).or_insert(&x);
+    /// assert_eq!(Some(&42), cloud.get("x"));
+    /// 
+    /// cloud.entry("x".to_string()).and_modify(|value| *value = &y).or_insert(&x);
+    /// assert_eq!(Some(&24), cloud.get("x"));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut &'a V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.entry.get_mut());
+        }
+        self
+    }
+
+    /// Stores `value` in the entry, replacing any previous reference, and returns the occupied entry.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// 
+    /// let entry = cloud.entry("x".to_string()).insert_entry(&x);
+    /// assert_eq!(&42, entry.get());
+    /// ```
+    pub fn insert_entry(self, value: &'a V) -> OccupiedEntry<'c, 'a, K, V, S> {
+        return match self {
+            Entry::Occupied(mut entry) => {
+                entry.insert(value);
+                entry
+            }
+            Entry::Vacant(VacantEntry { entry, _nodes }) => OccupiedEntry {
+                entry: entry.insert_entry(value),
+                _nodes,
+            },
+        }
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized, S> OccupiedEntry<'c, 'a, K, V, S> {
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &K {
+        return self.entry.key()
+    }
+
+    /// Returns the reference stored in the entry.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::entry::Entry;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// if let Entry::Occupied(entry) = cloud.entry("x".to_string()) {
+    ///     assert_eq!(&42, entry.get());
+    /// }
+    /// assert_eq!(Some(&42), cloud.get("x"));
+    /// ```
+    pub fn get(&self) -> &'a V {
+        let &value = self.entry.get();
+        return value
+    }
+
+    /// Replaces the reference stored in the entry and returns the old one.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::entry::Entry;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// if let Entry::Occupied(mut entry) = cloud.entry("x".to_string()) {
+    ///     assert_eq!(&42, entry.insert(&y));
+    /// }
+    /// assert_eq!(Some(&24), cloud.get("x"));
+    /// ```
+    pub fn insert(&mut self, value: &'a V) -> &'a V {
+        return self.entry.insert(value)
+    }
+
+    /// Removes the entry from the cloud and returns the reference it stored.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::entry::Entry;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// if let Entry::Occupied(entry) = cloud.entry("x".to_string()) {
+    ///     assert_eq!(&42, entry.remove());
+    /// }
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn remove(self) -> &'a V {
+        return self.entry.remove()
+    }
+
+    /// Removes the entry from the cloud and returns the stored key-value pair.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::entry::Entry;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// if let Entry::Occupied(entry) = cloud.entry("x".to_string()) {
+    ///     assert_eq!(("x".to_string(), &42), entry.remove_entry());
+    /// }
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn remove_entry(self) -> (K, &'a V) {
+        return self.entry.remove_entry()
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized, S> VacantEntry<'c, 'a, K, V, S> {
+    /// Returns a reference to the key that would be used when inserting through this entry.
+    pub fn key(&self) -> &K {
+        return self.entry.key()
+    }
+
+    /// Takes ownership of the key back, leaving the cloud untouched.
+    pub fn into_key(self) -> K {
+        return self.entry.into_key()
+    }
+
+    /// Stores `value` under the entry's key and returns it.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::entry::Entry;
+    /// 
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// let x = 42;
+    /// 
+    /// if let Entry::Vacant(entry) = cloud.entry("x".to_string()) {
+    ///     assert_eq!(&42, entry.insert(&x));
+    /// }
+    /// assert_eq!(Some(&42), cloud.get("x"));
+    /// ```
+    pub fn insert(self, value: &'a V) -> &'a V {
+        let &mut value = self.entry.insert(value);
+        return value
+    }
+}
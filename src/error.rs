@@ -1,6 +1,8 @@
 use std::{
+    collections::TryReserveError,
     error::Error,
-    fmt::Display
+    fmt::{Debug, Display},
+    io
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,4 +14,163 @@ impl Display for NullPointerError {
     }
 }
 
-impl Error for NullPointerError {}
\ No newline at end of file
+impl Error for NullPointerError {}
+
+/// The error carried by [`CloudError::Occupied`] when [`DataCloud::try_insert`](crate::DataCloud::try_insert)
+/// finds the key already occupied.
+/// 
+/// It carries both the reference already stored under the key and the one that was not inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OccupiedError<'a, V: ?Sized> {
+    /// The reference already stored under the key.
+    pub existing: &'a V,
+    /// The reference that was not inserted.
+    pub value: &'a V,
+}
+
+impl<'a, V: Debug + ?Sized> Display for OccupiedError<'a, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OccupiedError: key already holds {:?}, {:?} was not inserted", self.existing, self.value)
+    }
+}
+
+impl<'a, V: Debug + ?Sized> Error for OccupiedError<'a, V> {}
+
+/// The error returned by a merge whose resolver refused a conflict, such as
+/// [`ErrorOnConflict`](crate::merge::ErrorOnConflict).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict<K> {
+    /// The key both sides of the merge held.
+    pub key: K,
+}
+
+impl<K: Debug> Display for MergeConflict<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MergeConflict: both clouds hold the key {:?}", self.key)
+    }
+}
+
+impl<K: Debug> Error for MergeConflict<K> {}
+
+/// The error returned by the fallible `try_` methods of a [`DataCloud`](crate::DataCloud).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloudError<'a, V: ?Sized> {
+    /// The cloud doesn't hold the requested key.
+    KeyNotFound,
+    /// The cloud was already borrowed in a way that conflicts with the operation, for example
+    /// because an iterator over it is alive.
+    BorrowConflict,
+    /// A raw pointer handed to the cloud was null, as reported by
+    /// [`DataCloud::try_insert_from_raw`](crate::DataCloud::try_insert_from_raw).
+    NullPointer,
+    /// The cloud couldn't allocate room for more pairs.
+    Capacity(TryReserveError),
+    /// The key is already occupied, as reported by [`DataCloud::try_insert`](crate::DataCloud::try_insert).
+    Occupied(OccupiedError<'a, V>),
+}
+
+impl<'a, V: Debug + ?Sized> Display for CloudError<'a, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloudError::KeyNotFound => write!(f, "CloudError: key not found"),
+            CloudError::BorrowConflict => write!(f, "CloudError: the cloud is already borrowed"),
+            CloudError::NullPointer => write!(f, "CloudError: null pointer"),
+            CloudError::Capacity(error) => write!(f, "CloudError: {}", error),
+            CloudError::Occupied(error) => write!(f, "CloudError: {}", error),
+        }
+    }
+}
+
+impl<'a, V: Debug + ?Sized> Error for CloudError<'a, V> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            CloudError::Capacity(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, V: ?Sized> From<NullPointerError> for CloudError<'a, V> {
+    fn from(_: NullPointerError) -> Self {
+        return CloudError::NullPointer;
+    }
+}
+
+impl<'a, V: ?Sized> From<OccupiedError<'a, V>> for CloudError<'a, V> {
+    fn from(error: OccupiedError<'a, V>) -> Self {
+        return CloudError::Occupied(error);
+    }
+}
+
+impl<'a, V: ?Sized> From<TryReserveError> for CloudError<'a, V> {
+    fn from(error: TryReserveError) -> Self {
+        return CloudError::Capacity(error);
+    }
+}
+
+/// The error returned by [`DataCloud::write_snapshot`](crate::DataCloud::write_snapshot) and
+/// [`CloudArena::read_snapshot`](crate::CloudArena::read_snapshot).
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The underlying writer or reader failed.
+    Io(io::Error),
+    /// The snapshot ended before the length its header announces.
+    Truncated,
+    /// The snapshot doesn't start with the `cloudr` magic number, so it isn't a snapshot at all.
+    BadMagic,
+    /// The snapshot was written in a format version this build can't read.
+    UnsupportedVersion(u16),
+    /// The snapshot was written by a cloud using another hasher than the one it is read into.
+    HasherMismatch {
+        /// The id of the hasher of the cloud being read into.
+        expected: u32,
+        /// The id recorded in the snapshot.
+        found: u32,
+    },
+    /// The checksum of the snapshot's body doesn't match the one in its header.
+    ChecksumMismatch {
+        /// The checksum recorded in the header.
+        expected: u32,
+        /// The checksum of the body that was read.
+        found: u32,
+    },
+    /// The body passed its checksum but doesn't decode, for example because a value index is
+    /// out of range or a string isn't valid UTF-8.
+    Corrupt(&'static str),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "SnapshotError: {}", error),
+            SnapshotError::Truncated => write!(f, "SnapshotError: the snapshot is truncated"),
+            SnapshotError::BadMagic => write!(f, "SnapshotError: not a cloudr snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "SnapshotError: unsupported format version {}", version),
+            SnapshotError::HasherMismatch { expected, found } => {
+                write!(f, "SnapshotError: written with hasher {}, read with hasher {}", found, expected)
+            }
+            SnapshotError::ChecksumMismatch { expected, found } => {
+                write!(f, "SnapshotError: checksum {:#010x} doesn't match the header's {:#010x}", found, expected)
+            }
+            SnapshotError::Corrupt(reason) => write!(f, "SnapshotError: corrupt snapshot, {}", reason),
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            SnapshotError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        return match error.kind() {
+            io::ErrorKind::UnexpectedEof => SnapshotError::Truncated,
+            _ => SnapshotError::Io(error),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Deref
+};
+
+use crate::alias;
+
+/// A value compared and hashed by its address instead of its contents.
+/// 
+/// Storing `&'a ByAddress<V>` in a cloud makes every value comparison of that cloud an identity
+/// check: `contains_value`, `PartialEq` between clouds and `diff` all ask "is this exact object
+/// stored?" rather than "is an equal value stored?". Slices must also have the same length to
+/// be the same object.
+/// 
+/// Turn a `&V` into a `&ByAddress<V>` with [`ByAddress::from_ref`]; the wrapper is transparent,
+/// so the reference keeps pointing to the same place.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::{ByAddress, DataCloud};
+/// 
+/// let (x, y) = (1, 1);
+/// let cloud: DataCloud<'_, &str, ByAddress<i32>> = DataCloud::new();
+/// cloud.insert("x", ByAddress::from_ref(&x));
+/// 
+/// assert!(cloud.contains_value(ByAddress::from_ref(&x)));
+/// assert!(!cloud.contains_value(ByAddress::from_ref(&y)));
+/// assert_eq!(1, **cloud.get("x").unwrap());
+/// 
+/// let text = String::from("hello");
+/// assert!(ByAddress::from_ref(&text[..1]) != ByAddress::from_ref(&text[..5]));
+/// ```
+#[repr(transparent)]
+pub struct ByAddress<V: ?Sized>(pub V);
+
+impl<V: ?Sized> ByAddress<V> {
+    /// Reinterprets a reference as a reference compared by address.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ByAddress;
+    /// 
+    /// let x = 63;
+    /// let wrapped = ByAddress::from_ref(&x);
+    /// 
+    /// assert!(std::ptr::eq(&x, &wrapped.0));
+    /// assert!(wrapped == ByAddress::from_ref(&x));
+    /// ```
+    pub fn from_ref(value: &V) -> &ByAddress<V> {
+        // SAFETY: `ByAddress` is `repr(transparent)` over `V`, so both references have the same
+        // layout and pointer metadata.
+        return unsafe { &*(value as *const V as *const ByAddress<V>) };
+    }
+
+    /// Returns the address the wrapper is compared and hashed by, along with the size of the
+    /// wrapped value.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ByAddress;
+    /// 
+    /// let x = 63;
+    /// 
+    /// assert_eq!(&x as *const i32 as *const (), ByAddress::from_ref(&x).addr());
+    /// ```
+    pub fn addr(&self) -> *const () {
+        return (self as *const Self).cast();
+    }
+}
+
+impl<V: ?Sized> PartialEq for ByAddress<V> {
+    fn eq(&self, other: &Self) -> bool {
+        return alias::same_referent(self, other);
+    }
+}
+
+impl<V: ?Sized> Eq for ByAddress<V> {}
+
+impl<V: ?Sized> Hash for ByAddress<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        alias::referent(self).hash(state);
+    }
+}
+
+impl<V: ?Sized> Deref for ByAddress<V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<V: ?Sized> AsRef<V> for ByAddress<V> {
+    fn as_ref(&self) -> &V {
+        return &self.0;
+    }
+}
+
+impl<V: Debug + ?Sized> Debug for ByAddress<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ByAddress({:?} @ {:p})", &self.0, self.addr())
+    }
+}
+
+impl<V: Display + ?Sized> Display for ByAddress<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
@@ -1,133 +1,940 @@
-use std::iter::FusedIterator;
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    collections::{btree_map, hash_map, BTreeMap, HashMap},
+    iter::FusedIterator,
+    ops::RangeBounds,
+    borrow::Borrow,
+    hash::{BuildHasher, Hash},
+};
 
+use indexmap::IndexMap;
+
+use crate::{alias, defer::Settle, Side};
+
+/// Erases the type of a borrow guard while keeping the `RefCell` borrowed, and returns it
+/// together with a plain reference to the map it guards.
+/// 
+/// # Safety
+/// The returned reference must not be used after the returned guard has been dropped.
+unsafe fn detach<'c, T>(guard: Ref<'c, T>) -> (&'c T, Ref<'c, ()>) {
+    let target: *const T = &*guard;
+    // SAFETY: `Ref::map` moves the borrow flag into the new guard without releasing it, and
+    // the caller keeps that guard alive for as long as it uses the reference.
+    return (unsafe { &*target }, Ref::map(guard, |_| &()));
+}
+
+/// Hands out a key of a detached map behind its own clone of the map's guard.
+/// 
+/// # Safety
+/// `key` must live in the map that `nodes` keeps borrowed.
+unsafe fn guard_key<'c, K>(nodes: &Ref<'c, ()>, key: &K) -> Ref<'c, K> {
+    let key: *const K = key;
+    // SAFETY: the cloned guard keeps the map, and so the key, borrowed.
+    return Ref::map(Ref::clone(nodes), |_| unsafe { &*key });
+}
+
+/// The mutable counterpart of `detach`.
+/// 
+/// # Safety
+/// The returned reference must not be used after the returned guard has been dropped.
+unsafe fn detach_mut<'c, T>(mut guard: RefMut<'c, T>) -> (&'c mut T, RefMut<'c, [(); 0]>) {
+    let target: *mut T = &mut *guard;
+    // SAFETY: see `detach`; the new guard keeps the cell exclusively borrowed.
+    return (unsafe { &mut *target }, RefMut::map(guard, |_| &mut []));
+}
+
+/// A consuming iterator over the `(K, V)` pairs of a cloud, in arbitrary order.
+/// 
+/// Returned by `DataCloud::into_pairs`.
 pub struct IntoPairs<K, V> {
-    pairs: Vec<(K, V)>,
+    inner: hash_map::IntoIter<K, V>,
 }
 
 impl<K, V> IntoPairs<K, V> {
-    pub fn new(pairs: Vec<(K, V)>) -> IntoPairs<K, V> {
+    pub(crate) fn new<S>(nodes: HashMap<K, V, S>) -> IntoPairs<K, V> {
         return Self {
-            pairs,
+            inner: nodes.into_iter(),
         }
     }
 }
 
-impl<'a, K, V> Iterator for IntoPairs<K, V> {
+impl<K, V> Iterator for IntoPairs<K, V> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        return self.pairs.pop();
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoPairs<K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V> FusedIterator for IntoPairs<K, V> { }
+
+/// A consuming iterator over the `(K, *const V)` pairs of a cloud, in arbitrary order.
+/// 
+/// Returned by `DataCloud::into_raw_pairs`.
+pub struct IntoRawPairs<'a, K, V: ?Sized> {
+    inner: hash_map::IntoIter<K, &'a V>,
+}
+
+impl<'a, K, V: ?Sized> IntoRawPairs<'a, K, V> {
+    pub(crate) fn new<S>(nodes: HashMap<K, &'a V, S>) -> IntoRawPairs<'a, K, V> {
+        return Self {
+            inner: nodes.into_iter(),
+        }
     }
 }
 
+impl<K, V: ?Sized> Iterator for IntoRawPairs<'_, K, V> {
+    type Item = (K, *const V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next().map(|(key, value)| (key, value as *const V));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> ExactSizeIterator for IntoRawPairs<'_, K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for IntoRawPairs<'_, K, V> { }
+
+/// A consuming iterator over the `(K, V)` pairs of a cloud, in arbitrary order.
+/// 
+/// Returned by `DataCloud::into_iter`.
 pub struct IntoIter<K, V> {
-    pairs: Vec<(K, V)>,
+    inner: hash_map::IntoIter<K, V>,
 }
 
 impl<K, V> IntoIter<K, V> {
-    pub fn new(pairs: Vec<(K, V)>) -> IntoIter<K, V> {
+    pub(crate) fn new<S>(nodes: HashMap<K, V, S>) -> IntoIter<K, V> {
         return Self {
-            pairs,
+            inner: nodes.into_iter(),
         }
     }
 }
 
-impl<'a, K, V> Iterator for IntoIter<K, V> {
+impl<K, V> Iterator for IntoIter<K, V> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        return self.pairs.pop();
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V> FusedIterator for IntoIter<K, V> { }
+
+/// A consuming iterator over the `(K, &'a mut V)` pairs of a `MutDataCloud`, in arbitrary order.
+/// 
+/// Returned by `MutDataCloud::into_iter`.
+pub struct MutIntoIter<'a, K, V: ?Sized> {
+    inner: hash_map::IntoIter<K, RefCell<&'a mut V>>,
+}
+
+impl<'a, K, V: ?Sized> MutIntoIter<'a, K, V> {
+    pub(crate) fn new<S>(nodes: HashMap<K, RefCell<&'a mut V>, S>) -> MutIntoIter<'a, K, V> {
+        return Self {
+            inner: nodes.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V: ?Sized> Iterator for MutIntoIter<'a, K, V> {
+    type Item = (K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next().map(|(key, value)| (key, value.into_inner()));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for IntoIter<K, V> {
+impl<K, V: ?Sized> ExactSizeIterator for MutIntoIter<'_, K, V> {
     fn len(&self) -> usize {
-        return self.pairs.len();
+        return self.inner.len();
     }
 }
 
-impl<'a, K, V> FusedIterator for IntoIter<K, V> { }
+impl<K, V: ?Sized> FusedIterator for MutIntoIter<'_, K, V> { }
 
-pub struct Map<K, V> {
-    pairs: Vec<(K, V)>,
+/// A lazy iterator that maps each `(&K, &&'a V)` pair of a cloud through a closure.
+/// 
+/// Returned by `DataCloud::map`. The cloud stays borrowed until this is dropped, and the
+/// mutations made in the meantime are applied when it is.
+pub struct Map<'c, 'a, K, V, F> {
+    inner: hash_map::Iter<'c, K, &'a V>,
+    _nodes: Ref<'c, ()>,
+    f: F,
+    // Dropped after `_nodes`, so the queued mutations can borrow the cloud mutably.
+    settle: Settle<'c>,
 }
 
-impl<K, V> Map<K, V> {
-    pub fn new(pairs: Vec<(K, V)>) -> Map<K, V> {
+impl<'c, 'a, K, V, F> Map<'c, 'a, K, V, F> {
+    pub(crate) fn new<S>(nodes: Ref<'c, HashMap<K, &'a V, S>>, f: F, settle: Settle<'c>) -> Map<'c, 'a, K, V, F> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
         return Self {
-            pairs,
+            inner: map.iter(),
+            _nodes: nodes,
+            f,
+            settle,
         }
     }
 }
 
-impl<'a, K, V> Iterator for Map<K, V> {
+impl<'c, 'a, K, V, F> Iterator for Map<'c, 'a, K, V, F>
+where
+    F: FnMut((&K, &&'a V)) -> (K, V),
+{
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        return self.pairs.pop();
+        let pair = self.inner.next()?;
+        let _reentry = self.settle.reentry();
+        return Some((self.f)(pair));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for Map<K, V> {
+impl<'c, 'a, K, V, F> ExactSizeIterator for Map<'c, 'a, K, V, F>
+where
+    F: FnMut((&K, &&'a V)) -> (K, V),
+{
     fn len(&self) -> usize {
-        return self.pairs.len();
+        return self.inner.len();
     }
 }
 
-impl<'a, K, V> FusedIterator for Map<K, V> { }
+impl<'c, 'a, K, V, F> FusedIterator for Map<'c, 'a, K, V, F>
+where
+    F: FnMut((&K, &&'a V)) -> (K, V),
+{ }
 
-pub struct Iter<'a, K, V> {
-    pairs: Vec<(&'a K, &'a &'a V)>,
+/// A borrowing iterator over the `(Ref<K>, &'a V)` pairs of a cloud, in arbitrary order.
+/// 
+/// Returned by `DataCloud::iter`. Each key is handed out behind its own `Ref` guard, so the
+/// cloud stays borrowed until both this iterator and every key it yielded are dropped.
+pub struct Iter<'c, 'a, K, V: ?Sized> {
+    inner: hash_map::Iter<'c, K, &'a V>,
+    nodes: Ref<'c, ()>,
 }
 
-impl<'a, K, V> Iter<'a, K, V> {
-    pub fn new(pairs: Vec<(&'a K, &'a &'a V)>) -> Iter<'a, K, V> {
+impl<'c, 'a, K, V: ?Sized> Iter<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: Ref<'c, HashMap<K, &'a V, S>>) -> Iter<'c, 'a, K, V> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
         return Self {
-            pairs,
+            inner: map.iter(),
+            nodes,
         }
     }
 }
 
-impl<'a, K, V> Iterator for Iter<'a, K, V> {
-    type Item = (&'a K, &'a &'a V);
+impl<'c, 'a, K, V: ?Sized> Iterator for Iter<'c, 'a, K, V> {
+    type Item = (Ref<'c, K>, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        return self.pairs.pop();
+        let (key, &value) = self.inner.next()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+impl<K, V: ?Sized> ExactSizeIterator for Iter<'_, '_, K, V> {
     fn len(&self) -> usize {
-        return self.pairs.len();
+        return self.inner.len();
     }
 }
 
-impl<'a, K, V> FusedIterator for Iter<'a, K, V> { }
+impl<K, V: ?Sized> FusedIterator for Iter<'_, '_, K, V> { }
 
-pub struct IterMut<'a, K, V> {
-    pairs: Vec<(&'a K, &'a mut &'a V)>,
+/// A mutable iterator over the `(&K, &mut &'a V)` pairs of a cloud, in arbitrary order.
+/// 
+/// Returned by `DataCloud::iter_mut`. It borrows the cloud mutably, so no guard is needed.
+pub struct IterMut<'c, 'a, K, V: ?Sized> {
+    inner: hash_map::IterMut<'c, K, &'a V>,
 }
 
-impl<'a, K, V> IterMut<'a, K, V> {
-    pub fn new(pairs: Vec<(&'a K, &'a mut &'a V)>) -> IterMut<K, V> {
+impl<'c, 'a, K, V: ?Sized> IterMut<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: &'c mut HashMap<K, &'a V, S>) -> IterMut<'c, 'a, K, V> {
         return Self {
-            pairs,
+            inner: nodes.iter_mut(),
         }
     }
 }
 
-impl<'a, K, V> Iterator for IterMut<'a, K, V> {
-    type Item = (&'a K, &'a mut &'a V);
+impl<'c, 'a, K, V: ?Sized> Iterator for IterMut<'c, 'a, K, V> {
+    type Item = (&'c K, &'c mut &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        return self.pairs.pop();
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> ExactSizeIterator for IterMut<'_, '_, K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for IterMut<'_, '_, K, V> { }
+
+/// A borrowing iterator over the keys of a cloud, each behind its own `Ref` guard.
+/// 
+/// Returned by `DataCloud::keys`.
+pub struct Keys<'c, 'a, K, V: ?Sized> {
+    inner: Iter<'c, 'a, K, V>,
+}
+
+impl<'c, 'a, K, V: ?Sized> Keys<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: Ref<'c, HashMap<K, &'a V, S>>) -> Keys<'c, 'a, K, V> {
+        return Self {
+            inner: Iter::new(nodes),
+        }
+    }
+}
+
+impl<'c, K, V: ?Sized> Iterator for Keys<'c, '_, K, V> {
+    type Item = Ref<'c, K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next().map(|(key, _)| key);
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> ExactSizeIterator for Keys<'_, '_, K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for Keys<'_, '_, K, V> { }
+
+/// A borrowing iterator over the keys of a cloud whose reference points to one given address.
+/// 
+/// Returned by `DataCloud::keys_for_ptr`. The cloud stays borrowed until both this iterator and
+/// every key it yielded are dropped.
+pub struct KeysForPtr<'c, 'a, K, V: ?Sized> {
+    inner: Iter<'c, 'a, K, V>,
+    value: &'c V,
+}
+
+impl<'c, 'a, K, V: ?Sized> KeysForPtr<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: Ref<'c, HashMap<K, &'a V, S>>, value: &'c V) -> KeysForPtr<'c, 'a, K, V> {
+        return Self {
+            inner: Iter::new(nodes),
+            value,
+        }
+    }
+}
+
+impl<'c, K, V: ?Sized> Iterator for KeysForPtr<'c, '_, K, V> {
+    type Item = Ref<'c, K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.value;
+        return self.inner.by_ref()
+            .find(|&(_, stored)| alias::same_referent(stored, value))
+            .map(|(key, _)| key);
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (0, self.inner.size_hint().1);
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for KeysForPtr<'_, '_, K, V> { }
+
+/// A borrowing iterator over the `&'a V` values of a cloud.
+/// 
+/// Returned by `DataCloud::values`. The cloud stays borrowed until this is dropped.
+pub struct Values<'c, 'a, K, V: ?Sized> {
+    inner: hash_map::Values<'c, K, &'a V>,
+    _nodes: Ref<'c, ()>,
+}
+
+impl<'c, 'a, K, V: ?Sized> Values<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: Ref<'c, HashMap<K, &'a V, S>>) -> Values<'c, 'a, K, V> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
+        return Self {
+            inner: map.values(),
+            _nodes: nodes,
+        }
+    }
+}
+
+impl<'a, K, V: ?Sized> Iterator for Values<'_, 'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next().copied();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> ExactSizeIterator for Values<'_, '_, K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for Values<'_, '_, K, V> { }
+
+/// A consuming iterator over the keys of a cloud.
+/// 
+/// Returned by `DataCloud::into_keys`.
+pub struct IntoKeys<K, V> {
+    inner: hash_map::IntoKeys<K, V>,
+}
+
+impl<K, V> IntoKeys<K, V> {
+    pub(crate) fn new<S>(nodes: HashMap<K, V, S>) -> IntoKeys<K, V> {
+        return Self {
+            inner: nodes.into_keys(),
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoKeys<K, V> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoKeys<K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V> FusedIterator for IntoKeys<K, V> { }
+
+/// A consuming iterator over the values of a cloud.
+/// 
+/// Returned by `DataCloud::into_values`.
+pub struct IntoValues<K, V> {
+    inner: hash_map::IntoValues<K, V>,
+}
+
+impl<K, V> IntoValues<K, V> {
+    pub(crate) fn new<S>(nodes: HashMap<K, V, S>) -> IntoValues<K, V> {
+        return Self {
+            inner: nodes.into_values(),
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoValues<K, V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoValues<K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V> FusedIterator for IntoValues<K, V> { }
+
+/// A draining iterator over the `(K, &'a V)` pairs of a cloud.
+/// 
+/// Returned by `DataCloud::drain`. The cloud stays mutably borrowed until this is dropped,
+/// and dropping it removes every pair that wasn't yielded yet.
+pub struct Drain<'c, 'a, K, V: ?Sized> {
+    // Declared before the guard so the remaining pairs are dropped while the cloud is still borrowed.
+    inner: hash_map::Drain<'c, K, &'a V>,
+    _nodes: RefMut<'c, [(); 0]>,
+}
+
+impl<'c, 'a, K, V: ?Sized> Drain<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: RefMut<'c, HashMap<K, &'a V, S>>) -> Drain<'c, 'a, K, V> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard and dropped first.
+        let (map, nodes) = unsafe { detach_mut(nodes) };
+        return Self {
+            inner: map.drain(),
+            _nodes: nodes,
+        }
+    }
+}
+
+impl<'a, K, V: ?Sized> Iterator for Drain<'_, 'a, K, V> {
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {
+impl<K, V: ?Sized> ExactSizeIterator for Drain<'_, '_, K, V> {
     fn len(&self) -> usize {
-        return self.pairs.len();
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for Drain<'_, '_, K, V> { }
+
+/// An iterator that removes and yields the `(K, &'a V)` pairs of a cloud matching a predicate.
+/// 
+/// Returned by `DataCloud::extract_if`. The cloud stays mutably borrowed until this is dropped;
+/// pairs that weren't visited yet stay in the cloud.
+pub struct ExtractIf<'c, 'a, K, V: ?Sized, F>
+where
+    F: FnMut(&K, &mut &'a V) -> bool,
+{
+    inner: hash_map::ExtractIf<'c, K, &'a V, F>,
+    _nodes: RefMut<'c, [(); 0]>,
+}
+
+impl<'c, 'a, K, V: ?Sized, F> ExtractIf<'c, 'a, K, V, F>
+where
+    F: FnMut(&K, &mut &'a V) -> bool,
+{
+    pub(crate) fn new<S>(nodes: RefMut<'c, HashMap<K, &'a V, S>>, predicate: F) -> ExtractIf<'c, 'a, K, V, F> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard and dropped first.
+        let (map, nodes) = unsafe { detach_mut(nodes) };
+        return Self {
+            inner: map.extract_if(predicate),
+            _nodes: nodes,
+        }
+    }
+}
+
+impl<'a, K, V: ?Sized, F> Iterator for ExtractIf<'_, 'a, K, V, F>
+where
+    F: FnMut(&K, &mut &'a V) -> bool,
+{
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<'a, K, V: ?Sized, F> FusedIterator for ExtractIf<'_, 'a, K, V, F>
+where
+    F: FnMut(&K, &mut &'a V) -> bool,
+{ }
+
+/// A borrowing iterator over the `(Ref<K>, &'a V)` pairs of an `OrderedDataCloud`, in insertion order.
+/// 
+/// Returned by `OrderedDataCloud::iter`. Each key is handed out behind its own `Ref` guard, so the
+/// cloud stays borrowed until both this iterator and every key it yielded are dropped.
+pub struct OrderedIter<'c, 'a, K, V: ?Sized> {
+    inner: indexmap::map::Iter<'c, K, &'a V>,
+    nodes: Ref<'c, ()>,
+}
+
+impl<'c, 'a, K, V: ?Sized> OrderedIter<'c, 'a, K, V> {
+    pub(crate) fn new<S>(nodes: Ref<'c, IndexMap<K, &'a V, S>>) -> OrderedIter<'c, 'a, K, V> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
+        return Self {
+            inner: map.iter(),
+            nodes,
+        }
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized> Iterator for OrderedIter<'c, 'a, K, V> {
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, &value) = self.inner.next()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> DoubleEndedIterator for OrderedIter<'_, '_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, &value) = self.inner.next_back()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+}
+
+impl<K, V: ?Sized> ExactSizeIterator for OrderedIter<'_, '_, K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for OrderedIter<'_, '_, K, V> { }
+
+/// A consuming iterator over the `(K, V)` pairs of an `OrderedDataCloud`, in insertion order.
+/// 
+/// Returned by `OrderedDataCloud::into_iter`.
+pub struct OrderedIntoIter<K, V> {
+    inner: indexmap::map::IntoIter<K, V>,
+}
+
+impl<K, V> OrderedIntoIter<K, V> {
+    pub(crate) fn new<S>(nodes: IndexMap<K, V, S>) -> OrderedIntoIter<K, V> {
+        return Self {
+            inner: nodes.into_iter(),
+        }
+    }
+}
+
+impl<K, V> Iterator for OrderedIntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V> DoubleEndedIterator for OrderedIntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.inner.next_back();
+    }
+}
+
+impl<K, V> ExactSizeIterator for OrderedIntoIter<K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V> FusedIterator for OrderedIntoIter<K, V> { }
+
+/// A borrowing iterator over the `(Ref<K>, &'a V)` pairs of a `BTreeDataCloud`, in key order.
+/// 
+/// Returned by `BTreeDataCloud::iter`. Each key is handed out behind its own `Ref` guard, so the
+/// cloud stays borrowed until both this iterator and every key it yielded are dropped.
+pub struct SortedIter<'c, 'a, K, V: ?Sized> {
+    inner: btree_map::Iter<'c, K, &'a V>,
+    nodes: Ref<'c, ()>,
+}
+
+impl<'c, 'a, K, V: ?Sized> SortedIter<'c, 'a, K, V> {
+    pub(crate) fn new(nodes: Ref<'c, BTreeMap<K, &'a V>>) -> SortedIter<'c, 'a, K, V> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
+        return Self {
+            inner: map.iter(),
+            nodes,
+        }
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized> Iterator for SortedIter<'c, 'a, K, V> {
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, &value) = self.inner.next()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> DoubleEndedIterator for SortedIter<'_, '_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, &value) = self.inner.next_back()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+}
+
+impl<K, V: ?Sized> ExactSizeIterator for SortedIter<'_, '_, K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for SortedIter<'_, '_, K, V> { }
+
+/// A borrowing iterator over the `(Ref<K>, &'a V)` pairs of a `BTreeDataCloud` whose keys fall in
+/// a range, in key order.
+/// 
+/// Returned by `BTreeDataCloud::range`. The cloud stays borrowed until both this iterator and every
+/// key it yielded are dropped.
+pub struct Range<'c, 'a, K, V: ?Sized> {
+    inner: btree_map::Range<'c, K, &'a V>,
+    nodes: Ref<'c, ()>,
+}
+
+impl<'c, 'a, K, V: ?Sized> Range<'c, 'a, K, V> {
+    pub(crate) fn new<Q, R>(nodes: Ref<'c, BTreeMap<K, &'a V>>, range: R) -> Range<'c, 'a, K, V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
+        return Self {
+            inner: map.range(range),
+            nodes,
+        }
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized> Iterator for Range<'c, 'a, K, V> {
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, &value) = self.inner.next()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V: ?Sized> DoubleEndedIterator for Range<'_, '_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, &value) = self.inner.next_back()?;
+        // SAFETY: `inner` walks the map that `nodes` guards.
+        return Some((unsafe { guard_key(&self.nodes, key) }, value));
+    }
+}
+
+impl<K, V: ?Sized> FusedIterator for Range<'_, '_, K, V> { }
+
+/// A consuming iterator over the `(K, V)` pairs of a `BTreeDataCloud`, in key order.
+/// 
+/// Returned by `BTreeDataCloud::into_iter`.
+pub struct SortedIntoIter<K, V> {
+    inner: btree_map::IntoIter<K, V>,
+}
+
+impl<K, V> SortedIntoIter<K, V> {
+    pub(crate) fn new(nodes: BTreeMap<K, V>) -> SortedIntoIter<K, V> {
+        return Self {
+            inner: nodes.into_iter(),
+        }
+    }
+}
+
+impl<K, V> Iterator for SortedIntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.inner.next();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.inner.size_hint();
+    }
+}
+
+impl<K, V> DoubleEndedIterator for SortedIntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.inner.next_back();
+    }
+}
+
+impl<K, V> ExactSizeIterator for SortedIntoIter<K, V> {
+    fn len(&self) -> usize {
+        return self.inner.len();
+    }
+}
+
+impl<K, V> FusedIterator for SortedIntoIter<K, V> { }
+
+/// A lazy view over the pairs whose keys are in both of two clouds.
+/// 
+/// Returned by `DataCloud::intersection`. Values come from the side it was asked for. Both clouds
+/// stay borrowed until the view and every key it yielded are dropped.
+pub struct Intersection<'c, 'a, K, V: ?Sized, S> {
+    inner: hash_map::Iter<'c, K, &'a V>,
+    nodes: Ref<'c, ()>,
+    other: Ref<'c, HashMap<K, &'a V, S>>,
+    side: Side,
+}
+
+impl<'c, 'a, K, V: ?Sized, S> Intersection<'c, 'a, K, V, S> {
+    pub(crate) fn new(nodes: Ref<'c, HashMap<K, &'a V, S>>, other: Ref<'c, HashMap<K, &'a V, S>>, side: Side) -> Intersection<'c, 'a, K, V, S> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
+        return Self {
+            inner: map.iter(),
+            nodes,
+            other,
+            side,
+        }
+    }
+}
+
+impl<'c, 'a, K: Hash + Eq, V: ?Sized, S: BuildHasher> Iterator for Intersection<'c, 'a, K, V, S> {
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, &value) in self.inner.by_ref() {
+            if let Some(&other) = self.other.get(key) {
+                let value = match self.side {
+                    Side::Left => value,
+                    Side::Right => other,
+                };
+                // SAFETY: `inner` walks the map that `nodes` guards.
+                return Some((unsafe { guard_key(&self.nodes, key) }, value));
+            }
+        }
+        return None;
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (0, Some(self.inner.len().min(self.other.len())));
+    }
+}
+
+impl<K: Hash + Eq, V: ?Sized, S: BuildHasher> FusedIterator for Intersection<'_, '_, K, V, S> { }
+
+/// A lazy view over the pairs of one cloud whose keys aren't in another.
+/// 
+/// Returned by `DataCloud::difference`. Both clouds stay borrowed until the view and every key it
+/// yielded are dropped.
+pub struct Difference<'c, 'a, K, V: ?Sized, S> {
+    inner: hash_map::Iter<'c, K, &'a V>,
+    nodes: Ref<'c, ()>,
+    other: Ref<'c, HashMap<K, &'a V, S>>,
+}
+
+impl<'c, 'a, K, V: ?Sized, S> Difference<'c, 'a, K, V, S> {
+    pub(crate) fn new(nodes: Ref<'c, HashMap<K, &'a V, S>>, other: Ref<'c, HashMap<K, &'a V, S>>) -> Difference<'c, 'a, K, V, S> {
+        // SAFETY: the map reference lives in `inner`, which is stored next to the guard.
+        let (map, nodes) = unsafe { detach(nodes) };
+        return Self {
+            inner: map.iter(),
+            nodes,
+            other,
+        }
+    }
+}
+
+impl<'c, 'a, K: Hash + Eq, V: ?Sized, S: BuildHasher> Iterator for Difference<'c, 'a, K, V, S> {
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, &value) in self.inner.by_ref() {
+            if !self.other.contains_key(key) {
+                // SAFETY: `inner` walks the map that `nodes` guards.
+                return Some((unsafe { guard_key(&self.nodes, key) }, value));
+            }
+        }
+        return None;
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (0, Some(self.inner.len()));
+    }
+}
+
+impl<K: Hash + Eq, V: ?Sized, S: BuildHasher> FusedIterator for Difference<'_, '_, K, V, S> { }
+
+/// A lazy view over the pairs whose keys are in exactly one of two clouds.
+/// 
+/// Returned by `DataCloud::symmetric_difference`. It yields the pairs only the first cloud holds,
+/// then the ones only the second cloud holds. Both clouds stay borrowed until the view and every
+/// key it yielded are dropped.
+pub struct SymmetricDifference<'c, 'a, K, V: ?Sized, S> {
+    left: Difference<'c, 'a, K, V, S>,
+    right: Difference<'c, 'a, K, V, S>,
+}
+
+impl<'c, 'a, K, V: ?Sized, S> SymmetricDifference<'c, 'a, K, V, S> {
+    pub(crate) fn new(left: Difference<'c, 'a, K, V, S>, right: Difference<'c, 'a, K, V, S>) -> SymmetricDifference<'c, 'a, K, V, S> {
+        return Self {
+            left,
+            right,
+        }
+    }
+}
+
+impl<'c, 'a, K: Hash + Eq, V: ?Sized, S: BuildHasher> Iterator for SymmetricDifference<'c, 'a, K, V, S> {
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.left.next().or_else(|| self.right.next());
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, left) = self.left.size_hint();
+        let (_, right) = self.right.size_hint();
+        return (0, left.zip(right).and_then(|(left, right)| left.checked_add(right)));
     }
 }
 
-impl<'a, K, V> FusedIterator for IterMut<'a, K, V> { }
\ No newline at end of file
+impl<K: Hash + Eq, V: ?Sized, S: BuildHasher> FusedIterator for SymmetricDifference<'_, '_, K, V, S> { }
@@ -41,7 +41,7 @@
 //! let x = 42;
 //! cloud.insert("key".to_string(), &x);
 
-//! if let Some(value) = cloud.get(&"key".to_string()) {
+//! if let Some(value) = cloud.get("key") {
 //!     println!("Value: {}", value); // Output: Value: 42
 //! }
 //! ```
@@ -56,7 +56,7 @@
 //! let x = 42;
 //! cloud.insert("key".to_string(), &x);
 
-//! if let Some(value) = cloud.remove(&"key".to_string()) {
+//! if let Some(value) = cloud.remove("key") {
 //!     println!("Removed value: {}", value); // Output: Removed value: 42
 //! }
 //! ```
@@ -71,7 +71,7 @@
 //! let x = 42;
 //! cloud.insert("key".to_string(), &x);
 
-//! if cloud.contains_key(&"key".to_string()) {
+//! if cloud.contains_key("key") {
 //!     println!("The key exists in the DataCloud.");
 //! }
 //! ```
@@ -120,10 +120,33 @@
 //! ## License
 //! Cloudr is licensed under the MIT License.
 
-#![feature(negative_impls)]
-#![allow(suspicious_auto_trait_impls)]
+#![allow(clippy::needless_return)]
 
 mod cloud;
+mod sync;
+mod sharded;
+mod ordered;
+mod btree;
+mod identity;
+mod bidi;
+mod arena;
+mod weak;
+mod defer;
 pub mod iter;
 pub mod error;
-pub use cloud::*;
\ No newline at end of file
+pub mod entry;
+pub mod merge;
+pub mod diff;
+pub mod alias;
+pub mod snapshot;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub use cloud::*;
+pub use sync::*;
+pub use sharded::*;
+pub use ordered::*;
+pub use btree::*;
+pub use identity::*;
+pub use bidi::*;
+pub use arena::*;
+pub use weak::*;
\ No newline at end of file
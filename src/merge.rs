@@ -0,0 +1,167 @@
+use std::{
+    collections::{hash_map, HashMap},
+    hash::{BuildHasher, Hash}
+};
+
+use crate::error::MergeConflict;
+
+/// Decides which reference to keep when two merged clouds hold the same key.
+/// 
+/// `left` is the reference from the cloud being merged into, and `right` the one from the cloud
+/// being merged in. Returning `None` refuses the conflict, which makes the whole merge fail with a
+/// [`MergeConflict`].
+/// 
+/// Besides [`KeepLeft`], [`KeepRight`] and [`ErrorOnConflict`], any `FnMut(&K, R, R) -> R`
+/// function is a resolver. Wrap closures in [`resolve_with`] so their argument lifetimes are
+/// inferred.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::DataCloud;
+/// use cloudr::merge::resolve_with;
+/// 
+/// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+/// cloud.insert("x", &1);
+/// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+/// other.insert("x", &2);
+/// 
+/// let (merged, _) = cloud.merge_by(&other, resolve_with(|_, left: &i32, right| left.max(right))).unwrap();
+/// assert_eq!(Some(&2), merged.get("x"));
+/// ```
+pub trait Resolver<K, R> {
+    /// Resolves a conflict on `key` between `left` and `right`.
+    fn resolve(&mut self, key: &K, left: R, right: R) -> Option<R>;
+}
+
+/// Keeps the reference that was already in the cloud being merged into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepLeft;
+
+/// Keeps the reference from the cloud being merged in. This is what `merge` and `merge_all` do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepRight;
+
+/// Refuses every conflict, making the merge fail on the first shared key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ErrorOnConflict;
+
+impl<K, R> Resolver<K, R> for KeepLeft {
+    fn resolve(&mut self, _key: &K, left: R, _right: R) -> Option<R> {
+        return Some(left);
+    }
+}
+
+impl<K, R> Resolver<K, R> for KeepRight {
+    fn resolve(&mut self, _key: &K, _left: R, right: R) -> Option<R> {
+        return Some(right);
+    }
+}
+
+impl<K, R> Resolver<K, R> for ErrorOnConflict {
+    fn resolve(&mut self, _key: &K, _left: R, _right: R) -> Option<R> {
+        return None;
+    }
+}
+
+impl<K, R, F: FnMut(&K, R, R) -> R> Resolver<K, R> for F {
+    fn resolve(&mut self, key: &K, left: R, right: R) -> Option<R> {
+        return Some(self(key, left, right));
+    }
+}
+
+/// Turns a closure into a [`Resolver`] over `&'a V` references.
+/// 
+/// This only helps type inference: a closure passed straight to a merge gets its own lifetime for
+/// every reference argument, and then can't return either of them.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::DataCloud;
+/// use cloudr::merge::resolve_with;
+/// 
+/// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+/// cloud.insert("x", &1);
+/// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+/// other.insert("x", &2);
+/// 
+/// let report = cloud.merge_in_place_by(other, resolve_with(|_, left: &i32, _| left)).unwrap();
+/// assert_eq!(vec!["x"], report.conflicts);
+/// ```
+pub fn resolve_with<'a, K, V: 'a + ?Sized, F>(resolver: F) -> F
+where
+    F: FnMut(&K, &'a V, &'a V) -> &'a V,
+{
+    return resolver;
+}
+
+/// The outcome of a merge: the merged collection and its report, or the conflict a resolver refused.
+pub type MergeResult<T, K> = Result<(T, MergeReport<K>), MergeConflict<K>>;
+
+/// What a successful merge ran into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeReport<K> {
+    /// The keys held by both sides of the merge, in the order they were resolved.
+    pub conflicts: Vec<K>,
+}
+
+impl<K> MergeReport<K> {
+    pub(crate) fn new() -> MergeReport<K> {
+        return MergeReport {
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Returns if the merge didn't run into any shared key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::DataCloud;
+    /// use cloudr::merge::KeepRight;
+    /// 
+    /// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// cloud.insert("x", &1);
+    /// let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    /// other.insert("y", &2);
+    /// 
+    /// let (_, report) = cloud.merge_by(&other, KeepRight).unwrap();
+    /// assert!(report.is_clean());
+    /// ```
+    pub fn is_clean(&self) -> bool {
+        return self.conflicts.is_empty();
+    }
+}
+
+impl<K> Default for MergeReport<K> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Inserts `pairs` into `nodes`, resolving every shared key with `resolver`.
+pub(crate) fn resolve_into<'a, K, V: ?Sized, S, R>(
+    nodes: &mut HashMap<K, &'a V, S>,
+    pairs: impl IntoIterator<Item = (K, &'a V)>,
+    resolver: &mut R,
+    report: &mut MergeReport<K>,
+) -> Result<(), MergeConflict<K>>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+    R: Resolver<K, &'a V>,
+{
+    for (key, value) in pairs {
+        match nodes.entry(key) {
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            hash_map::Entry::Occupied(mut entry) => {
+                let Some(resolved) = resolver.resolve(entry.key(), *entry.get(), value) else {
+                    return Err(MergeConflict { key: entry.key().clone() });
+                };
+                report.conflicts.push(entry.key().clone());
+                entry.insert(resolved);
+            }
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,468 @@
+use std::{
+    borrow::Borrow,
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    hash::{BuildHasher, Hash},
+    fmt::{Debug, Display}
+};
+
+use indexmap::IndexMap;
+
+use crate::{
+    iter::{OrderedIntoIter, OrderedIter},
+    DefaultHashBuilder
+};
+
+/// A DataCloud that remembers the order its keys were inserted in.
+/// 
+/// Iteration, [`into_vec`](OrderedDataCloud::into_vec), `Debug` and `Display` all follow that
+/// order, and pairs can be addressed by position. Like a [`DataCloud`](crate::DataCloud), it only
+/// stores `&'a V` references and never moves the values.
+/// 
+/// Inserting a key that is already present replaces its reference but keeps its position.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::OrderedDataCloud;
+/// 
+/// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+/// cloud.insert("b".to_string(), &2);
+/// cloud.insert("a".to_string(), &1);
+/// 
+/// assert_eq!(vec![("b".to_string(), &2), ("a".to_string(), &1)], cloud.into_vec());
+/// ```
+pub struct OrderedDataCloud<'a, K: PartialEq + Eq + Hash, V: ?Sized, S = DefaultHashBuilder> {
+    nodes: RefCell<IndexMap<K, &'a V, S>>,
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized> OrderedDataCloud<'a, K, V> {
+    /// Returns a new instance of an OrderedDataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// ```
+    pub fn new() -> OrderedDataCloud<'a, K, V> {
+        return OrderedDataCloud::with_hasher(Default::default())
+    }
+
+    /// Returns a new instance of an OrderedDataCloud with room for at least `capacity` pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::with_capacity(16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> OrderedDataCloud<'a, K, V> {
+        return OrderedDataCloud::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> OrderedDataCloud<'a, K, V, S> {
+    /// Returns a new instance of an OrderedDataCloud which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32, RandomState> = OrderedDataCloud::with_hasher(RandomState::new());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> OrderedDataCloud<'a, K, V, S> {
+        return OrderedDataCloud {
+            nodes: RefCell::new(IndexMap::with_hasher(hash_builder)),
+        }
+    }
+
+    /// Returns a new instance of an OrderedDataCloud with room for at least `capacity` pairs,
+    /// which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32, RandomState> = OrderedDataCloud::with_capacity_and_hasher(16, RandomState::new());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> OrderedDataCloud<'a, K, V, S> {
+        return OrderedDataCloud {
+            nodes: RefCell::new(IndexMap::with_capacity_and_hasher(capacity, hash_builder)),
+        }
+    }
+
+    /// Returns the number of key-value pairs in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// cloud.insert("x".to_string(), &63);
+    /// 
+    /// assert_eq!(1, cloud.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.nodes.borrow().len();
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.borrow().is_empty();
+    }
+
+    /// Returns the key-value pair at `index`, with the key behind a `Ref` guard.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// cloud.insert("x".to_string(), &1);
+    /// cloud.insert("y".to_string(), &2);
+    /// 
+    /// let (key, value) = cloud.get_index(1).unwrap();
+    /// assert_eq!(("y", &2), (key.as_str(), value));
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(Ref<'_, K>, &'a V)> {
+        let nodes = self.nodes.borrow();
+        let &value = nodes.get_index(index)?.1;
+        return Some((Ref::map(nodes, |nodes| nodes.get_index(index).unwrap().0), value))
+    }
+
+    /// Removes every key-value pair from the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// cloud.insert("x".to_string(), &1);
+    /// cloud.clear();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.nodes.borrow_mut().clear();
+    }
+
+    /// Moves the pair at index `from` to index `to`, shifting the pairs in between.
+    /// 
+    /// # Panics
+    /// Panics if `from` or `to` are out of bounds.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// cloud.insert("c", &3);
+    /// 
+    /// cloud.move_index(2, 0);
+    /// 
+    /// assert_eq!(vec![("c", &3), ("a", &1), ("b", &2)], cloud.into_vec());
+    /// ```
+    pub fn move_index(&self, from: usize, to: usize) {
+        self.nodes.borrow_mut().move_index(from, to);
+    }
+
+    /// Sorts the pairs of the cloud in place with the given comparison function.
+    /// 
+    /// The sort is stable.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &3);
+    /// cloud.insert("b", &1);
+    /// cloud.insert("c", &2);
+    /// 
+    /// cloud.sort_by(|_, v1, _, v2| v1.cmp(v2));
+    /// 
+    /// assert_eq!(vec![("b", &1), ("c", &2), ("a", &3)], cloud.into_vec());
+    /// ```
+    pub fn sort_by<F>(&self, mut cmp: F)
+    where
+        F: FnMut(&K, &&'a V, &K, &&'a V) -> Ordering,
+    {
+        self.nodes.borrow_mut().sort_by(|k1, v1, k2, v2| cmp(k1, v1, k2, v2));
+    }
+
+    /// Sorts the pairs of the cloud in place by key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("b", &1);
+    /// cloud.insert("a", &2);
+    /// 
+    /// cloud.sort_keys();
+    /// 
+    /// assert_eq!(vec![("a", &2), ("b", &1)], cloud.into_vec());
+    /// ```
+    pub fn sort_keys(&self)
+    where
+        K: Ord,
+    {
+        self.nodes.borrow_mut().sort_keys();
+    }
+
+    /// Returns an iterator over the pairs of the cloud in order, with each key behind a `Ref` guard.
+    /// 
+    /// The cloud stays borrowed until the iterator and every key it yielded are dropped.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// 
+    /// let last = cloud.iter().next_back().map(|(key, value)| (*key, value));
+    /// assert_eq!(Some(("b", &2)), last);
+    /// ```
+    pub fn iter(&self) -> OrderedIter<'_, 'a, K, V> {
+        return OrderedIter::new(self.nodes.borrow());
+    }
+
+    /// Consumes the cloud and returns its pairs in order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// 
+    /// assert_eq!(vec![("a", &1)], cloud.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a V)> {
+        return self.nodes.into_inner().into_iter().collect();
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> OrderedDataCloud<'a, K, V, S> {
+    /// Inserts a new key at the end of the cloud, or replaces the reference of an existing key
+    /// in place. Returns the replaced reference, if any.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// 
+    /// assert_eq!(Some(&1), cloud.insert("a", &3));
+    /// assert_eq!(vec![("a", &3), ("b", &2)], cloud.into_vec());
+    /// ```
+    pub fn insert(&self, key: K, value: &'a V) -> Option<&'a V> {
+        return self.nodes.borrow_mut().insert(key, value);
+    }
+
+    /// Gets the reference stored under the given key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// cloud.insert("x".to_string(), &1);
+    /// 
+    /// assert_eq!(Some(&1), cloud.get("x"));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow().get(key).copied()
+    }
+
+    /// Returns the position of the given key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// cloud.insert("x".to_string(), &1);
+    /// cloud.insert("y".to_string(), &2);
+    /// 
+    /// assert_eq!(Some(1), cloud.get_index_of("y"));
+    /// ```
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow().get_index_of(key)
+    }
+
+    /// Returns if the cloud contains the specified key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, String, i32> = OrderedDataCloud::new();
+    /// cloud.insert("x".to_string(), &1);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow().contains_key(key)
+    }
+
+    /// Removes a key by swapping the last pair into its place. This is O(1) but perturbs the
+    /// order of the last pair.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// cloud.insert("c", &3);
+    /// 
+    /// assert_eq!(Some(&1), cloud.swap_remove("a"));
+    /// assert_eq!(vec![("c", &3), ("b", &2)], cloud.into_vec());
+    /// ```
+    pub fn swap_remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow_mut().swap_remove(key)
+    }
+
+    /// Removes a key by shifting every following pair down. This is O(n) but keeps the order of
+    /// the remaining pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// cloud.insert("c", &3);
+    /// 
+    /// assert_eq!(Some(&1), cloud.shift_remove("a"));
+    /// assert_eq!(vec![("b", &2), ("c", &3)], cloud.into_vec());
+    /// ```
+    pub fn shift_remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.borrow_mut().shift_remove(key)
+    }
+
+    /// Retains only the pairs for which `predicate` returns `true`, keeping their order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::OrderedDataCloud;
+    /// 
+    /// let cloud: OrderedDataCloud<'_, &str, i32> = OrderedDataCloud::new();
+    /// cloud.insert("a", &1);
+    /// cloud.insert("b", &2);
+    /// cloud.insert("c", &3);
+    /// 
+    /// cloud.retain(|_, value| **value != 2);
+    /// 
+    /// assert_eq!(vec![("a", &1), ("c", &3)], cloud.into_vec());
+    /// ```
+    pub fn retain<F>(&self, mut predicate: F)
+    where
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        self.nodes.borrow_mut().retain(|key, value| predicate(key, value));
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> Extend<(K, &'a V)> for OrderedDataCloud<'a, K, V, S> {
+    fn extend<T: IntoIterator<Item = (K, &'a V)>>(&mut self, iter: T) {
+        self.nodes.get_mut().extend(iter);
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher + Default> FromIterator<(K, &'a V)> for OrderedDataCloud<'a, K, V, S> {
+    fn from_iter<T: IntoIterator<Item = (K, &'a V)>>(iter: T) -> Self {
+        return Self {
+            nodes: RefCell::new(IndexMap::from_iter(iter)),
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: Default> Default for OrderedDataCloud<'a, K, V, S> {
+    fn default() -> Self {
+        return Self::with_hasher(S::default());
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> IntoIterator for OrderedDataCloud<'a, K, V, S> {
+    type IntoIter = OrderedIntoIter<K, &'a V>;
+    type Item = (K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        OrderedIntoIter::new(self.nodes.into_inner())
+    }
+}
+
+impl<'c, 'a, K: PartialEq + Eq + Hash, V: ?Sized, S> IntoIterator for &'c OrderedDataCloud<'a, K, V, S> {
+    type IntoIter = OrderedIter<'c, 'a, K, V>;
+    type Item = (Ref<'c, K>, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        OrderedIter::new(self.nodes.borrow())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Debug, V: Debug + ?Sized, S> Debug for OrderedDataCloud<'a, K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = String::from("OrderedCloud {\n");
+        for (k, v) in self.nodes.borrow().iter() {
+            output.push_str(&format!("    ({:?}: {:?}), \n", k, v));
+        }
+        output.remove(output.len() - 1);
+        output.push('}');
+        write!(f, "{}", output)
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Display, V: Display + ?Sized, S> Display for OrderedDataCloud<'a, K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = String::from("{\n");
+        for (k, v) in self.nodes.borrow().iter() {
+            output.push_str(&format!("    ({}: {}), \n", k, v));
+        }
+        output.remove(output.len() - 1);
+        output.push('}');
+        write!(f, "{}", output)
+    }
+}
@@ -0,0 +1,289 @@
+use std::{
+    fmt::{self, Formatter},
+    hash::{BuildHasher, Hash},
+    marker::PhantomData
+};
+
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor},
+    ser::{SerializeSeq, SerializeStruct, Serializer},
+    Deserialize,
+    Serialize
+};
+
+use crate::{alias, CloudArena, DataCloud};
+
+const FIELDS: &[&str] = &["values", "keys"];
+
+/// How a cloud treats keys whose references point to the same value.
+/// 
+/// With [`Sharing::Duplicate`], the default, a cloud is serialized as a plain map: every key is
+/// written with a copy of its value, and a deserialized cloud gets one value per key. With
+/// [`Sharing::Preserve`], every referenced value is written once, followed by the keys and the
+/// position of their value, so aliased keys come back aliased.
+/// 
+/// A cloud must be deserialized with the same option it was serialized with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sharing {
+    /// Writes a copy of the value for every key.
+    #[default]
+    Duplicate,
+    /// Writes every referenced value once.
+    Preserve,
+}
+
+/// Serializes a cloud as a map from each key to the value it references.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::DataCloud;
+/// 
+/// let x = 1;
+/// let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+/// cloud.insert("x", &x);
+/// 
+/// assert_eq!(r#"{"x":1}"#, serde_json::to_string(&cloud).unwrap());
+/// ```
+impl<'a, K, V, S> Serialize for DataCloud<'a, K, V, S>
+where
+    K: PartialEq + Eq + Hash + Serialize,
+    V: Serialize + ?Sized,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        return serializer.collect_map(self.nodes.borrow().iter());
+    }
+}
+
+/// A cloud serialized with a [`Sharing`] option.
+/// 
+/// Returned by [`DataCloud::serialize_with`].
+pub struct SerializeCloud<'c, 'a, K: PartialEq + Eq + Hash, V: ?Sized, S> {
+    cloud: &'c DataCloud<'a, K, V, S>,
+    sharing: Sharing,
+}
+
+impl<'c, 'a, K: PartialEq + Eq + Hash, V: ?Sized, S> SerializeCloud<'c, 'a, K, V, S> {
+    pub(crate) fn new(cloud: &'c DataCloud<'a, K, V, S>, sharing: Sharing) -> SerializeCloud<'c, 'a, K, V, S> {
+        return SerializeCloud { cloud, sharing };
+    }
+}
+
+impl<'c, 'a, K, V, S> Serialize for SerializeCloud<'c, 'a, K, V, S>
+where
+    K: PartialEq + Eq + Hash + Serialize,
+    V: Serialize + ?Sized,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        if self.sharing == Sharing::Duplicate {
+            return self.cloud.serialize(serializer);
+        }
+        let nodes = self.cloud.nodes.borrow();
+        let groups = alias::group_by_address(nodes.iter().map(|(key, &value)| (key, value)));
+        let mut state = serializer.serialize_struct("DataCloud", FIELDS.len())?;
+        state.serialize_field("values", &Values(&groups))?;
+        state.serialize_field("keys", &Keys(&groups))?;
+        return state.end();
+    }
+}
+
+/// The referenced values of a cloud with preserved sharing, in group order.
+struct Values<'g, 'p, K, V: ?Sized>(&'g [(&'p V, Vec<&'p K>)]);
+
+/// The `(key, position of its value)` pairs of a cloud with preserved sharing.
+struct Keys<'g, 'p, K, V: ?Sized>(&'g [(&'p V, Vec<&'p K>)]);
+
+impl<K, V: Serialize + ?Sized> Serialize for Values<'_, '_, K, V> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        return serializer.collect_seq(self.0.iter().map(|(value, _)| value));
+    }
+}
+
+impl<K: Serialize, V: ?Sized> Serialize for Keys<'_, '_, K, V> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        // Some formats need the length upfront, which a flattened iterator doesn't give.
+        let mut seq = serializer.serialize_seq(Some(self.0.iter().map(|(_, keys)| keys.len()).sum()))?;
+        for (position, (_, keys)) in self.0.iter().enumerate() {
+            for key in keys {
+                seq.serialize_element(&(key, position))?;
+            }
+        }
+        return seq.end();
+    }
+}
+
+/// Deserializes a cloud, moving its values into an arena so the references it holds stay valid.
+/// 
+/// A `DataCloud` can't implement `Deserialize` on its own, because something has to own the
+/// values it references. `CloudSeed` is the [`DeserializeSeed`] that supplies that owner; most
+/// callers go through [`CloudArena::deserialize_cloud`] instead.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::{CloudArena, DataCloud};
+/// use cloudr::serde::CloudSeed;
+/// use serde::de::DeserializeSeed;
+/// 
+/// let arena = CloudArena::new();
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{"x":1,"y":2}"#);
+/// let cloud: DataCloud<'_, String, i32> = CloudSeed::new(&arena).deserialize(&mut deserializer).unwrap();
+/// 
+/// assert_eq!(Some(&2), cloud.get("y"));
+/// assert_eq!(2, arena.len());
+/// ```
+pub struct CloudSeed<'a, K, V, S = crate::DefaultHashBuilder> {
+    arena: &'a CloudArena<V>,
+    sharing: Sharing,
+    marker: PhantomData<fn() -> (K, S)>,
+}
+
+impl<'a, K, V, S> CloudSeed<'a, K, V, S> {
+    /// Returns a seed that deserializes a plain map into `arena`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::CloudArena;
+    /// use cloudr::serde::CloudSeed;
+    /// 
+    /// let arena: CloudArena<i32> = CloudArena::new();
+    /// let seed: CloudSeed<'_, String, i32> = CloudSeed::new(&arena);
+    /// ```
+    pub fn new(arena: &'a CloudArena<V>) -> CloudSeed<'a, K, V, S> {
+        return CloudSeed::with_sharing(arena, Sharing::Duplicate);
+    }
+
+    /// Returns a seed that deserializes a cloud serialized with `sharing` into `arena`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{CloudArena, DataCloud};
+    /// use cloudr::serde::{CloudSeed, Sharing};
+    /// use serde::de::DeserializeSeed;
+    /// 
+    /// let x = 1;
+    /// let cloud: DataCloud<'_, String, i32> = DataCloud::new();
+    /// cloud.insert("a".to_string(), &x);
+    /// cloud.insert("b".to_string(), &x);
+    /// let bytes = postcard::to_allocvec(&cloud.serialize_with(Sharing::Preserve)).unwrap();
+    /// 
+    /// let arena = CloudArena::new();
+    /// let seed = CloudSeed::with_sharing(&arena, Sharing::Preserve);
+    /// let copy: DataCloud<'_, String, i32> = seed.deserialize(&mut postcard::Deserializer::from_bytes(&bytes)).unwrap();
+    /// 
+    /// assert!(std::ptr::eq(copy.get("a").unwrap(), copy.get("b").unwrap()));
+    /// assert_eq!(1, arena.len());
+    /// ```
+    pub fn with_sharing(arena: &'a CloudArena<V>, sharing: Sharing) -> CloudSeed<'a, K, V, S> {
+        return CloudSeed { arena, sharing, marker: PhantomData };
+    }
+}
+
+impl<'a, 'de, K, V, S> DeserializeSeed<'de> for CloudSeed<'a, K, V, S>
+where
+    K: PartialEq + Eq + Hash + Deserialize<'de>,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = DataCloud<'a, K, V, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        return match self.sharing {
+            Sharing::Duplicate => deserializer.deserialize_map(self),
+            Sharing::Preserve => deserializer.deserialize_struct("DataCloud", FIELDS, self),
+        }
+    }
+}
+
+impl<'a, 'de, K, V, S> Visitor<'de> for CloudSeed<'a, K, V, S>
+where
+    K: PartialEq + Eq + Hash + Deserialize<'de>,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = DataCloud<'a, K, V, S>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        return match self.sharing {
+            Sharing::Duplicate => formatter.write_str("a map of keys to values"),
+            Sharing::Preserve => formatter.write_str("a cloud with its values and the positions they are referenced by"),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        if self.sharing == Sharing::Preserve {
+            let (mut values, mut keys) = (None, None);
+            while let Some(field) = map.next_key::<Field>()? {
+                match field {
+                    Field::Values if values.is_some() => return Err(de::Error::duplicate_field("values")),
+                    Field::Keys if keys.is_some() => return Err(de::Error::duplicate_field("keys")),
+                    Field::Values => values = Some(map.next_value()?),
+                    Field::Keys => keys = Some(map.next_value()?),
+                }
+            }
+            let values = values.ok_or_else(|| de::Error::missing_field("values"))?;
+            let keys = keys.ok_or_else(|| de::Error::missing_field("keys"))?;
+            return self.link(values, keys);
+        }
+
+        // Don't trust the size hint with more than a page worth of pairs.
+        let capacity = map.size_hint().unwrap_or(0).min(4096);
+        let cloud = DataCloud::with_capacity_and_hasher(capacity, S::default());
+        while let Some((key, value)) = map.next_entry()? {
+            cloud.insert(key, self.arena.alloc(value));
+        }
+        return Ok(cloud);
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        if self.sharing == Sharing::Duplicate {
+            return Err(de::Error::invalid_type(Unexpected::Seq, &self));
+        }
+        let values = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let keys = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        return self.link(values, keys);
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V, S: BuildHasher + Default> CloudSeed<'a, K, V, S> {
+    /// Moves the values into the arena and points every key to the value at its position.
+    fn link<E: de::Error>(self, values: Vec<V>, keys: Vec<(K, usize)>) -> Result<DataCloud<'a, K, V, S>, E> {
+        let values: Vec<&'a V> = values.into_iter().map(|value| self.arena.alloc(value)).collect();
+        let cloud = DataCloud::with_capacity_and_hasher(keys.len(), S::default());
+        for (key, position) in keys {
+            let Some(&value) = values.get(position) else {
+                return Err(E::invalid_value(Unexpected::Unsigned(position as u64), &"the position of a serialized value"));
+            };
+            cloud.insert(key, value);
+        }
+        return Ok(cloud);
+    }
+}
+
+/// A field of a cloud serialized with [`Sharing::Preserve`].
+enum Field {
+    Values,
+    Keys,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Field, D::Error> {
+        return deserializer.deserialize_identifier(FieldVisitor);
+    }
+}
+
+struct FieldVisitor;
+
+impl Visitor<'_> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        return formatter.write_str("`values` or `keys`");
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+        return match value {
+            "values" => Ok(Field::Values),
+            "keys" => Ok(Field::Keys),
+            _ => Err(E::unknown_field(value, FIELDS)),
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    fmt::Debug,
+    sync::RwLock,
+    thread
+};
+
+use crate::{DefaultHashBuilder, SyncReadGuard, SyncWriteGuard};
+
+type Shard<'a, K, V, S> = RwLock<HashMap<K, &'a V, S>>;
+
+/// A thread-safe cloud that splits its keys across independently locked shards.
+/// 
+/// Keys are assigned to a shard by their hash, so writers touching different shards never wait
+/// on each other. Single-key operations lock exactly one shard and are linearizable; operations
+/// spanning the whole cloud, like [`ShardedCloud::len`] or [`ShardedCloud::retain`], visit the
+/// shards one at a time and are not atomic with respect to concurrent writers.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::ShardedCloud;
+/// 
+/// let cloud: ShardedCloud<'_, usize, usize> = ShardedCloud::new();
+/// let values: Vec<usize> = (0..64).collect();
+/// 
+/// std::thread::scope(|scope| {
+///     for chunk in values.chunks(16) {
+///         let cloud = &cloud;
+///         scope.spawn(move || {
+///             for value in chunk {
+///                 cloud.insert(*value, value);
+///             }
+///         });
+///     }
+/// });
+/// 
+/// assert_eq!(64, cloud.len());
+/// assert_eq!(Some(&42), cloud.get(&42));
+/// ```
+pub struct ShardedCloud<'a, K: PartialEq + Eq + Hash, V: ?Sized, S = DefaultHashBuilder> {
+    hash_builder: S,
+    shards: Box<[Shard<'a, K, V, S>]>,
+}
+
+/// An iterator over the shards of a [`ShardedCloud`], read-locking each one as it is yielded.
+pub struct Shards<'c, 'a, K, V: ?Sized, S> {
+    shards: std::slice::Iter<'c, Shard<'a, K, V, S>>,
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized> ShardedCloud<'a, K, V> {
+    /// Returns a new instance of a ShardedCloud with a shard count suited to the machine's parallelism.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// assert!(cloud.shard_count().is_power_of_two());
+    /// ```
+    pub fn new() -> ShardedCloud<'a, K, V> {
+        return ShardedCloud::with_hasher(Default::default())
+    }
+
+    /// Returns a new instance of a ShardedCloud with `shard_count` shards, rounded up to a power of two.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::with_shards(6);
+    /// assert_eq!(8, cloud.shard_count());
+    /// ```
+    pub fn with_shards(shard_count: usize) -> ShardedCloud<'a, K, V> {
+        return ShardedCloud::with_shards_and_hasher(shard_count, Default::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: Clone> ShardedCloud<'a, K, V, S> {
+    /// Returns a new instance of a ShardedCloud which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32, RandomState> = ShardedCloud::with_hasher(RandomState::new());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> ShardedCloud<'a, K, V, S> {
+        let parallelism = thread::available_parallelism().map_or(1, |count| count.get());
+        return ShardedCloud::with_shards_and_hasher(parallelism * 4, hash_builder)
+    }
+
+    /// Returns a new instance of a ShardedCloud with `shard_count` shards, rounded up to a power of two,
+    /// which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32, RandomState> = ShardedCloud::with_shards_and_hasher(16, RandomState::new());
+    /// assert_eq!(16, cloud.shard_count());
+    /// ```
+    pub fn with_shards_and_hasher(shard_count: usize, hash_builder: S) -> ShardedCloud<'a, K, V, S> {
+        let shard_count = shard_count.max(1).next_power_of_two();
+        let shards = (0..shard_count)
+            .map(|_| RwLock::new(HashMap::with_hasher(hash_builder.clone())))
+            .collect();
+        return ShardedCloud {
+            hash_builder,
+            shards,
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> ShardedCloud<'a, K, V, S> {
+    /// Returns the number of shards of the cloud.
+    pub fn shard_count(&self) -> usize {
+        return self.shards.len()
+    }
+
+    /// Returns an iterator over the shards of the cloud, read-locking each shard as it is reached.
+    /// 
+    /// Only one shard is locked at a time as long as the previous guard is dropped before moving on.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, usize, usize> = ShardedCloud::with_shards(4);
+    /// let values: Vec<usize> = (0..10).collect();
+    /// for value in &values {
+    ///     cloud.insert(*value, value);
+    /// }
+    /// 
+    /// let mut sum = 0;
+    /// for shard in cloud.shards() {
+    ///     sum += shard.iter().map(|(_, value)| *value).sum::<usize>();
+    /// }
+    /// assert_eq!(45, sum);
+    /// ```
+    pub fn shards(&self) -> Shards<'_, 'a, K, V, S> {
+        return Shards {
+            shards: self.shards.iter(),
+        }
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> ShardedCloud<'a, K, V, S> {
+    fn shard<Q>(&self, key: &Q) -> &Shard<'a, K, V, S>
+    where
+        Q: Hash + ?Sized,
+    {
+        // The shard maps hash with the same builder and pick buckets from the low bits and
+        // tags from the top bits, so the shard is picked from the bits in between. The high half
+        // is folded in first, since hashers like FxHasher only yield 32-bit hashes on 32-bit
+        // targets.
+        let hash = self.hash_builder.hash_one(key);
+        let hash = hash ^ (hash >> 32);
+        return &self.shards[(hash as usize >> 7) & (self.shards.len() - 1)]
+    }
+
+    /// Inserts a new key into the cloud, returning the reference it replaced.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// let y = 3;
+    /// assert_eq!(None, cloud.insert("y".to_string(), &y));
+    /// ```
+    pub fn insert(&self, key: K, value: &'a V) -> Option<&'a V> {
+        return SyncWriteGuard::lock(self.shard(&key)).insert(key, value)
+    }
+
+    /// Gets the reference stored in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// assert_eq!(Some(&3), cloud.get("y"));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return SyncReadGuard::lock(self.shard(key)).get(key)
+    }
+
+    /// Removes the reference stored in the cloud and returns it if it exists.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// assert_eq!(Some(&3), cloud.remove("y"));
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return SyncWriteGuard::lock(self.shard(key)).remove(key)
+    }
+
+    /// Returns if the cloud contains a reference indexed by this key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return SyncReadGuard::lock(self.shard(key)).contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the cloud, counting one shard at a time.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// assert_eq!(1, cloud.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.shards().map(|shard| shard.len()).sum()
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.shards().all(|shard| shard.is_empty())
+    }
+
+    /// Clears the cloud's key-value pairs, one shard at a time.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, usize, i128> = ShardedCloud::new();
+    /// let v = 54;
+    /// cloud.insert(3, &v);
+    /// 
+    /// cloud.clear();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            SyncWriteGuard::lock(shard).clear();
+        }
+    }
+
+    /// Retains only the elements specified by the predicate function, one shard at a time.
+    /// 
+    /// Each shard is write-locked while the predicate runs over it, so the predicate must not access the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<String, i32> = ShardedCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// cloud.insert("y".to_string(), &24);
+    /// 
+    /// cloud.retain(|_key, value| **value > 30);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// assert!(!cloud.contains_key("y"));
+    /// ```
+    pub fn retain<F>(&self, mut predicate: F)
+    where
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        for shard in self.shards.iter() {
+            SyncWriteGuard::lock(shard).retain(&mut predicate);
+        }
+    }
+
+    /// Consumes the ShardedCloud and returns a vector of tuples containing `(K, &'a V)`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::ShardedCloud;
+    /// 
+    /// let cloud: ShardedCloud<'_, String, i32> = ShardedCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// assert_eq!(vec![("y".to_string(), &3)], cloud.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a V)> {
+        return self.shards.into_vec()
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap_or_else(std::sync::PoisonError::into_inner))
+            .collect()
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized, S> Iterator for Shards<'c, 'a, K, V, S> {
+    type Item = SyncReadGuard<'c, 'a, K, V, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.shards.next().map(SyncReadGuard::lock)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.shards.size_hint()
+    }
+}
+
+impl<'c, 'a, K, V: ?Sized, S> ExactSizeIterator for Shards<'c, 'a, K, V, S> {}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: Default + Clone> Default for ShardedCloud<'a, K, V, S> {
+    fn default() -> Self {
+        return ShardedCloud::with_hasher(S::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher + Default + Clone> FromIterator<(K, &'a V)> for ShardedCloud<'a, K, V, S> {
+    fn from_iter<T: IntoIterator<Item = (K, &'a V)>>(iter: T) -> Self {
+        let cloud = ShardedCloud::default();
+        for (key, value) in iter {
+            cloud.insert(key, value);
+        }
+        cloud
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Debug, V: Debug + ?Sized, S: BuildHasher> Debug for ShardedCloud<'a, K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+        for shard in self.shards() {
+            map.entries(shard.iter());
+        }
+        return map.finish()
+    }
+}
@@ -0,0 +1,492 @@
+use std::{
+    collections::hash_map::{DefaultHasher, RandomState},
+    hash::{BuildHasher, BuildHasherDefault, Hash},
+    io::{Read, Write}
+};
+
+use crate::{alias, error::SnapshotError, CloudArena, DataCloud};
+
+/// The bytes every snapshot starts with.
+pub const MAGIC: [u8; 8] = *b"CLOUDRSN";
+
+/// The snapshot format version written by this build, and the only one it reads.
+pub const VERSION: u16 = 1;
+
+/// A value that can be written to a snapshot.
+/// 
+/// Integers and floats are written in little-endian, and strings and sequences are prefixed with
+/// their length as a `u64`.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::snapshot::Encode;
+/// 
+/// let mut out = Vec::new();
+/// "hi".encode(&mut out);
+/// 
+/// assert_eq!(vec![2, 0, 0, 0, 0, 0, 0, 0, b'h', b'i'], out);
+/// ```
+pub trait Encode {
+    /// Appends the encoding of `self` to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// A value that can be read back from a snapshot, the inverse of [`Encode`].
+/// 
+/// # Examples
+/// ```
+/// use cloudr::snapshot::{Decode, Decoder, Encode};
+/// 
+/// let mut out = Vec::new();
+/// vec![Some(1u16), None].encode(&mut out);
+/// 
+/// let mut decoder = Decoder::new(&out);
+/// assert_eq!(vec![Some(1u16), None], Vec::<Option<u16>>::decode(&mut decoder).unwrap());
+/// assert!(decoder.is_empty());
+/// ```
+pub trait Decode: Sized {
+    /// Reads a value from the front of `input`.
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError>;
+}
+
+/// The body of a snapshot being decoded.
+pub struct Decoder<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Decoder<'b> {
+    /// Returns a decoder over `bytes`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::snapshot::Decoder;
+    /// 
+    /// let decoder = Decoder::new(&[1, 2, 3]);
+    /// 
+    /// assert_eq!(3, decoder.remaining());
+    /// ```
+    pub fn new(bytes: &'b [u8]) -> Decoder<'b> {
+        return Decoder { bytes };
+    }
+
+    /// Takes the next `len` bytes, failing with [`SnapshotError::Corrupt`] if there are fewer left.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::snapshot::Decoder;
+    /// 
+    /// let mut decoder = Decoder::new(&[1, 2, 3]);
+    /// 
+    /// assert_eq!(&[1, 2], decoder.take(2).unwrap());
+    /// assert!(decoder.take(2).is_err());
+    /// ```
+    pub fn take(&mut self, len: usize) -> Result<&'b [u8], SnapshotError> {
+        if len > self.bytes.len() {
+            return Err(SnapshotError::Corrupt("a value runs past the end of the body"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        return Ok(taken);
+    }
+
+    /// Reads the length prefix of a byte string, checking that at least that many bytes are left
+    /// so a corrupt length can't trigger a huge allocation.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::snapshot::Decoder;
+    /// 
+    /// let mut decoder = Decoder::new(&[200, 0, 0, 0, 0, 0, 0, 0]);
+    /// 
+    /// assert!(decoder.len_prefix().is_err());
+    /// ```
+    pub fn len_prefix(&mut self) -> Result<usize, SnapshotError> {
+        let len = usize::try_from(u64::decode(self)?).unwrap_or(usize::MAX);
+        if len > self.bytes.len() {
+            return Err(SnapshotError::Corrupt("a length is larger than the rest of the body"));
+        }
+        return Ok(len);
+    }
+
+    /// Returns the number of bytes left.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::snapshot::Decoder;
+    /// 
+    /// assert_eq!(0, Decoder::new(&[]).remaining());
+    /// ```
+    pub fn remaining(&self) -> usize {
+        return self.bytes.len();
+    }
+
+    /// Returns if every byte has been decoded.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::snapshot::Decoder;
+    /// 
+    /// assert!(Decoder::new(&[]).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.bytes.is_empty();
+    }
+}
+
+/// Identifies the hasher of a cloud in the header of its snapshots, so a snapshot is only read
+/// back into a cloud hashing keys the same way.
+/// 
+/// Custom hashers pick their own id; ids below 256 are reserved for this crate.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::snapshot::HasherId;
+/// use std::hash::{BuildHasher, DefaultHasher};
+/// 
+/// #[derive(Default)]
+/// struct Seeded;
+/// 
+/// impl BuildHasher for Seeded {
+///     type Hasher = DefaultHasher;
+///     fn build_hasher(&self) -> DefaultHasher { DefaultHasher::new() }
+/// }
+/// 
+/// impl HasherId for Seeded {
+///     const HASHER_ID: u32 = 0x5eed;
+/// }
+/// ```
+pub trait HasherId {
+    /// The id written to the snapshot header.
+    const HASHER_ID: u32;
+}
+
+impl HasherId for RandomState {
+    const HASHER_ID: u32 = 1;
+}
+
+impl HasherId for BuildHasherDefault<DefaultHasher> {
+    const HASHER_ID: u32 = 2;
+}
+
+#[cfg(feature = "fxhash")]
+impl HasherId for fxhash::FxBuildHasher {
+    const HASHER_ID: u32 = 3;
+}
+
+macro_rules! impl_le_bytes {
+    ($($number:ty),*) => {
+        $(
+            impl Encode for $number {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl Decode for $number {
+                fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+                    let bytes = input.take(std::mem::size_of::<$number>())?;
+                    return Ok(<$number>::from_le_bytes(bytes.try_into().unwrap()));
+                }
+            }
+        )*
+    };
+}
+
+impl_le_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Encode for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+}
+
+impl Decode for usize {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return usize::try_from(u64::decode(input)?).map_err(|_| SnapshotError::Corrupt("a usize doesn't fit this platform"));
+    }
+}
+
+impl Encode for isize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as i64).encode(out);
+    }
+}
+
+impl Decode for isize {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return isize::try_from(i64::decode(input)?).map_err(|_| SnapshotError::Corrupt("an isize doesn't fit this platform"));
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SnapshotError::Corrupt("a bool is neither 0 nor 1")),
+        }
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+}
+
+impl Decode for char {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return char::from_u32(u32::decode(input)?).ok_or(SnapshotError::Corrupt("a char isn't a Unicode scalar value"));
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().encode(out);
+    }
+}
+
+impl Decode for String {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        let len = input.len_prefix()?;
+        let bytes = input.take(len)?;
+        return String::from_utf8(bytes.to_vec()).map_err(|_| SnapshotError::Corrupt("a string isn't valid UTF-8"));
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for element in self {
+            element.encode(out);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        let len = usize::decode(input)?;
+        // Elements may take no bytes at all, so the length can't be checked against what's left,
+        // only the upfront allocation can be bounded.
+        let mut elements = Vec::with_capacity(len.min(input.remaining()));
+        for _ in 0..len {
+            elements.push(T::decode(input)?);
+        }
+        return Ok(elements);
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.is_some().encode(out);
+        if let Some(value) = self {
+            value.encode(out);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return match bool::decode(input)? {
+            true => T::decode(input).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for Box<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+impl<T: Decode> Decode for Box<T> {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return T::decode(input).map(Box::new);
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+impl Encode for () {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+}
+
+impl Decode for () {
+    fn decode(_input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return Ok(());
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return Ok((A::decode(input)?, B::decode(input)?));
+    }
+}
+
+impl<A: Encode, B: Encode, C: Encode> Encode for (A, B, C) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+        self.2.encode(out);
+    }
+}
+
+impl<A: Decode, B: Decode, C: Decode> Decode for (A, B, C) {
+    fn decode(input: &mut Decoder<'_>) -> Result<Self, SnapshotError> {
+        return Ok((A::decode(input)?, B::decode(input)?, C::decode(input)?));
+    }
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    return table;
+}
+
+/// The CRC-32 (IEEE) of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    return !crc;
+}
+
+/// Writes the snapshot of a cloud: the header, then the body with every referenced value once
+/// and the keys with the index of their value.
+pub(crate) fn write<'a, K, V, S, W>(cloud: &DataCloud<'a, K, V, S>, mut writer: W) -> Result<(), SnapshotError>
+where
+    K: PartialEq + Eq + Hash + Encode,
+    V: Encode + ?Sized,
+    S: HasherId,
+    W: Write,
+{
+    let nodes = cloud.nodes.borrow();
+    let groups = alias::group_by_address(nodes.iter().map(|(key, &value)| (key, value)));
+    let mut body = Vec::new();
+    groups.len().encode(&mut body);
+    for (value, _) in &groups {
+        value.encode(&mut body);
+    }
+    nodes.len().encode(&mut body);
+    for (index, (_, keys)) in groups.iter().enumerate() {
+        for key in keys {
+            key.encode(&mut body);
+            index.encode(&mut body);
+        }
+    }
+
+    let mut header = Vec::with_capacity(26);
+    header.extend_from_slice(&MAGIC);
+    VERSION.encode(&mut header);
+    S::HASHER_ID.encode(&mut header);
+    body.len().encode(&mut header);
+    crc32(&body).encode(&mut header);
+    writer.write_all(&header)?;
+    writer.write_all(&body)?;
+    writer.flush()?;
+    return Ok(());
+}
+
+/// Reads a snapshot, moving its values into `arena`.
+pub(crate) fn read<'a, K, V, S, R>(arena: &'a CloudArena<V>, mut reader: R) -> Result<DataCloud<'a, K, V, S>, SnapshotError>
+where
+    K: PartialEq + Eq + Hash + Decode,
+    V: Decode,
+    S: BuildHasher + Default + HasherId,
+    R: Read,
+{
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+    let mut header = [0; 18];
+    reader.read_exact(&mut header)?;
+    let mut header = Decoder::new(&header);
+    let version = u16::decode(&mut header)?;
+    if version != VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+    let hasher = u32::decode(&mut header)?;
+    if hasher != S::HASHER_ID {
+        return Err(SnapshotError::HasherMismatch { expected: S::HASHER_ID, found: hasher });
+    }
+    let len = u64::decode(&mut header)?;
+    let checksum = u32::decode(&mut header)?;
+
+    // Read through `take` rather than allocating `len` bytes upfront, which a corrupt header could
+    // make arbitrarily large.
+    let mut body = Vec::new();
+    reader.take(len).read_to_end(&mut body)?;
+    if (body.len() as u64) < len {
+        return Err(SnapshotError::Truncated);
+    }
+    let found = crc32(&body);
+    if found != checksum {
+        return Err(SnapshotError::ChecksumMismatch { expected: checksum, found });
+    }
+
+    let mut body = Decoder::new(&body);
+    let count = usize::decode(&mut body)?;
+    arena.reserve(count.min(body.remaining()));
+    let mut values = Vec::with_capacity(count.min(body.remaining()));
+    for _ in 0..count {
+        values.push(arena.alloc(V::decode(&mut body)?));
+    }
+    let count = usize::decode(&mut body)?;
+    let cloud = DataCloud::with_capacity_and_hasher(count.min(body.remaining()), S::default());
+    for _ in 0..count {
+        let key = K::decode(&mut body)?;
+        let index = usize::decode(&mut body)?;
+        let value = values.get(index).ok_or(SnapshotError::Corrupt("a key points past the last value"))?;
+        cloud.insert(key, *value);
+    }
+    if !body.is_empty() {
+        return Err(SnapshotError::Corrupt("the body continues after the last key"));
+    }
+    return Ok(cloud);
+}
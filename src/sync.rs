@@ -0,0 +1,604 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    fmt::Debug,
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard}
+};
+
+use crate::{DataCloud, DefaultHashBuilder};
+
+/// A thread-safe DataCloud backed by a reader-writer lock.
+/// 
+/// Every method takes the lock for the duration of a single operation. To run several
+/// operations under one lock, use [`SyncDataCloud::read`] or [`SyncDataCloud::write`].
+/// 
+/// The lock is never considered poisoned: no operation can leave the map of references
+/// half-updated, so a panic in another thread doesn't make the cloud unusable.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::SyncDataCloud;
+/// 
+/// let cloud: SyncDataCloud<'_, usize, usize> = SyncDataCloud::new();
+/// let values: Vec<usize> = (0..8).collect();
+/// 
+/// std::thread::scope(|scope| {
+///     for value in &values {
+///         let cloud = &cloud;
+///         scope.spawn(move || cloud.insert(*value, value));
+///     }
+/// });
+/// 
+/// assert_eq!(8, cloud.len());
+/// ```
+/// 
+/// Like a [`DataCloud`], it doesn't need its values to implement `Eq`, or even to be sized:
+/// ```
+/// use cloudr::{DataCloud, SyncDataCloud};
+/// 
+/// let weights: DataCloud<'_, &str, f64> = DataCloud::new();
+/// weights.insert("pi", &3.14);
+/// 
+/// let weights = SyncDataCloud::from(weights);
+/// assert_eq!(Some(&3.14), weights.get("pi"));
+/// 
+/// let names: SyncDataCloud<'_, i32, str> = SyncDataCloud::new();
+/// names.insert(1, "one");
+/// assert!(names.contains_value("one"));
+/// ```
+pub struct SyncDataCloud<'a, K: PartialEq + Eq + Hash, V: ?Sized, S = DefaultHashBuilder> {
+    nodes: RwLock<HashMap<K, &'a V, S>>,
+}
+
+/// A read guard over a [`SyncDataCloud`] or one shard of a [`ShardedCloud`](crate::ShardedCloud),
+/// holding its lock in shared mode.
+pub struct SyncReadGuard<'g, 'a, K, V: ?Sized, S> {
+    nodes: RwLockReadGuard<'g, HashMap<K, &'a V, S>>,
+}
+
+/// A write guard over a [`SyncDataCloud`] or one shard of a [`ShardedCloud`](crate::ShardedCloud),
+/// holding its lock in exclusive mode.
+pub struct SyncWriteGuard<'g, 'a, K, V: ?Sized, S> {
+    nodes: RwLockWriteGuard<'g, HashMap<K, &'a V, S>>,
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized> SyncDataCloud<'a, K, V> {
+    /// Returns a new instance of a SyncDataCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// ```
+    pub fn new() -> SyncDataCloud<'a, K, V> {
+        return SyncDataCloud::with_hasher(Default::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> SyncDataCloud<'a, K, V, S> {
+    /// Returns a new instance of a SyncDataCloud which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32, RandomState> = SyncDataCloud::with_hasher(RandomState::new());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> SyncDataCloud<'a, K, V, S> {
+        return SyncDataCloud::from_hashmap(HashMap::with_hasher(hash_builder))
+    }
+
+    /// Builds a new SyncDataCloud from a `HashMap<K, &'a V, S>`, keeping its hasher.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// use fxhash::FxHashMap;
+    /// 
+    /// let mut map: FxHashMap<String, &i32> = FxHashMap::default();
+    /// let y = 3;
+    /// map.insert("y".to_string(), &y);
+    /// 
+    /// let cloud = SyncDataCloud::from_hashmap(map);
+    /// ```
+    pub fn from_hashmap(hashmap: HashMap<K, &'a V, S>) -> SyncDataCloud<'a, K, V, S> {
+        return SyncDataCloud {
+            nodes: RwLock::new(hashmap),
+        }
+    }
+
+    /// Locks the cloud for reading, blocking until no writer holds the lock.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// let guard = cloud.read();
+    /// assert_eq!(Some(&42), guard.get("x"));
+    /// assert!(!guard.contains_key("y"));
+    /// ```
+    pub fn read(&self) -> SyncReadGuard<'_, 'a, K, V, S> {
+        return SyncReadGuard::lock(&self.nodes)
+    }
+
+    /// Locks the cloud for writing, blocking until no other reader or writer holds the lock.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// let mut guard = cloud.write();
+    /// if !guard.contains_key("x") {
+    ///     guard.insert("x".to_string(), &x);
+    /// }
+    /// guard.insert("y".to_string(), &y);
+    /// drop(guard);
+    /// 
+    /// assert_eq!(2, cloud.len());
+    /// ```
+    pub fn write(&self) -> SyncWriteGuard<'_, 'a, K, V, S> {
+        return SyncWriteGuard::lock(&self.nodes)
+    }
+
+    /// Consumes the SyncDataCloud and returns a single-threaded [`DataCloud`] with the same pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::{DataCloud, SyncDataCloud};
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// let local: DataCloud<'_, String, i32> = cloud.into_local();
+    /// assert_eq!(Some(&42), local.get("x"));
+    /// ```
+    pub fn into_local(self) -> DataCloud<'a, K, V, S> {
+        return DataCloud::from_hashmap(self.nodes.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher> SyncDataCloud<'a, K, V, S> {
+    /// Inserts a new key into the cloud, returning the reference it replaced.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let y = 3;
+    /// let inserted_before: Option<&i32> = cloud.insert("y".to_string(), &y);
+    /// ```
+    pub fn insert(&self, key: K, value: &'a V) -> Option<&'a V> {
+        return self.write().insert(key, value)
+    }
+
+    /// Gets the reference stored in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// assert_eq!(Some(&3), cloud.get("y"));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.read().get(key)
+    }
+
+    /// Removes the reference stored in the cloud and returns it if it exists.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// assert_eq!(Some(&3), cloud.remove("y"));
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.write().remove(key)
+    }
+
+    /// Returns if the cloud contains a reference indexed by this key.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.read().contains_key(key)
+    }
+
+    /// Returns if the cloud contains the specified reference as a value.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// assert!(cloud.contains_value(&63));
+    /// ```
+    pub fn contains_value(&self, value: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        return self.read().nodes.values().any(|stored| *stored == value)
+    }
+
+    /// Returns the number of key-value pairs in the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 63;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// assert_eq!(1, cloud.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.read().len()
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.read().is_empty()
+    }
+
+    /// Clears the cloud's key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, usize, i128> = SyncDataCloud::new();
+    /// let v = 54;
+    /// cloud.insert(3, &v);
+    /// 
+    /// cloud.clear();
+    /// 
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    /// Retains only the elements specified by the predicate function.
+    /// 
+    /// The write lock is held while the predicate runs, so the predicate must not access the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<String, i32> = SyncDataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// cloud.insert("y".to_string(), &24);
+    /// 
+    /// cloud.retain(|_key, value| **value > 30);
+    /// 
+    /// assert!(cloud.contains_key("x"));
+    /// assert!(!cloud.contains_key("y"));
+    /// ```
+    pub fn retain<F>(&self, predicate: F)
+    where
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        self.write().retain(predicate);
+    }
+
+    /// Inserts multiple elements at a time under a single lock.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 42;
+    /// let y = 24;
+    /// 
+    /// cloud.insert_all(vec![("x".to_string(), &x), ("y".to_string(), &y)]);
+    /// assert_eq!(2, cloud.len());
+    /// ```
+    pub fn insert_all(&self, pairs: Vec<(K, &'a V)>) -> Vec<Option<&'a V>> {
+        let mut nodes = self.write();
+        let mut out = Vec::new();
+        for (key, value) in pairs {
+            out.push(nodes.insert(key, value));
+        }
+        out
+    }
+
+    /// Merges in place the SyncDataCloud with the other one by consuming the other SyncDataCloud.
+    /// The other cloud has priority on conflicting keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// let cloud2: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 56;
+    /// cloud2.insert("x".to_string(), &x);
+    /// 
+    /// cloud.merge_in_place(cloud2);
+    /// assert_eq!(2, cloud.len());
+    /// ```
+    pub fn merge_in_place(&self, other: SyncDataCloud<'a, K, V, S>) {
+        let other = other.nodes.into_inner().unwrap_or_else(PoisonError::into_inner);
+        self.write().nodes.extend(other);
+    }
+
+    /// Consumes the SyncDataCloud and returns a vector of tuples containing `(K, &'a V)`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let y = 3;
+    /// cloud.insert("y".to_string(), &y);
+    /// 
+    /// assert_eq!(vec![("y".to_string(), &3)], cloud.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<(K, &'a V)> {
+        return self.nodes.into_inner().unwrap_or_else(PoisonError::into_inner).into_iter().collect()
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Clone, V: ?Sized, S: BuildHasher + Clone> SyncDataCloud<'a, K, V, S> {
+    /// Merges the SyncDataCloud with another and returns the resulting one.
+    /// The other cloud has priority on conflicting keys.
+    /// 
+    /// Each cloud is read-locked in turn while its pairs are copied.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<String, i32> = SyncDataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// 
+    /// let cloud2: SyncDataCloud<String, i32> = SyncDataCloud::new();
+    /// cloud2.insert("z".to_string(), &64);
+    /// 
+    /// assert!(cloud.merge(&cloud2).contains_key("z"));
+    /// ```
+    pub fn merge(&self, other: &SyncDataCloud<'a, K, V, S>) -> SyncDataCloud<'a, K, V, S> {
+        return self.merge_all(vec![other])
+    }
+
+    /// Merges the SyncDataCloud with other instances and returns the resulting one.
+    /// The last element of `others` has priority on conflicting keys.
+    /// 
+    /// Each cloud is read-locked in turn while its pairs are copied.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<String, i32> = SyncDataCloud::new();
+    /// cloud.insert("x".to_string(), &42);
+    /// 
+    /// let cloud2: SyncDataCloud<String, i32> = SyncDataCloud::new();
+    /// cloud2.insert("x".to_string(), &64);
+    /// 
+    /// assert_eq!(Some(&64), cloud.merge_all(vec![&cloud2]).get("x"));
+    /// ```
+    pub fn merge_all(&self, others: Vec<&SyncDataCloud<'a, K, V, S>>) -> SyncDataCloud<'a, K, V, S> {
+        let mut nodes = self.read().nodes.clone();
+        for other in others {
+            nodes.extend(other.read().nodes.iter().map(|(key, value)| (key.clone(), *value)));
+        }
+        return SyncDataCloud::from_hashmap(nodes)
+    }
+}
+
+impl<'g, 'a, K, V: ?Sized, S> SyncReadGuard<'g, 'a, K, V, S> {
+    pub(crate) fn lock(nodes: &'g RwLock<HashMap<K, &'a V, S>>) -> SyncReadGuard<'g, 'a, K, V, S> {
+        return SyncReadGuard {
+            nodes: nodes.read().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+}
+
+impl<'g, 'a, K: Eq + Hash, V: ?Sized, S: BuildHasher> SyncReadGuard<'g, 'a, K, V, S> {
+    /// Gets the reference stored in the cloud.
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.get(key).copied()
+    }
+
+    /// Returns if the cloud contains a reference indexed by this key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the cloud.
+    pub fn len(&self) -> usize {
+        return self.nodes.len()
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty()
+    }
+
+    /// Returns an iterator over the key-value pairs of the cloud in arbitrary order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::SyncDataCloud;
+    /// 
+    /// let cloud: SyncDataCloud<'_, String, i32> = SyncDataCloud::new();
+    /// let x = 42;
+    /// cloud.insert("x".to_string(), &x);
+    /// 
+    /// let guard = cloud.read();
+    /// let pairs: Vec<(&String, &i32)> = guard.iter().collect();
+    /// assert_eq!(vec![(&"x".to_string(), &42)], pairs);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &'a V)> + '_ {
+        return self.nodes.iter().map(|(key, value)| (key, *value))
+    }
+}
+
+impl<'g, 'a, K, V: ?Sized, S> SyncWriteGuard<'g, 'a, K, V, S> {
+    pub(crate) fn lock(nodes: &'g RwLock<HashMap<K, &'a V, S>>) -> SyncWriteGuard<'g, 'a, K, V, S> {
+        return SyncWriteGuard {
+            nodes: nodes.write().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+}
+
+impl<'g, 'a, K: Eq + Hash, V: ?Sized, S: BuildHasher> SyncWriteGuard<'g, 'a, K, V, S> {
+    /// Inserts a new key into the cloud, returning the reference it replaced.
+    pub fn insert(&mut self, key: K, value: &'a V) -> Option<&'a V> {
+        return self.nodes.insert(key, value)
+    }
+
+    /// Gets the reference stored in the cloud.
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.get(key).copied()
+    }
+
+    /// Removes the reference stored in the cloud and returns it if it exists.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.remove(key)
+    }
+
+    /// Returns if the cloud contains a reference indexed by this key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.nodes.contains_key(key)
+    }
+
+    /// Returns the number of key-value pairs in the cloud.
+    pub fn len(&self) -> usize {
+        return self.nodes.len()
+    }
+
+    /// Returns if the cloud does not contain any key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty()
+    }
+
+    /// Clears the cloud's key-value pairs.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    /// Retains only the elements specified by the predicate function.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&K, &&'a V) -> bool,
+    {
+        self.nodes.retain(|key, value| predicate(key, value));
+    }
+
+    /// Returns an iterator over the key-value pairs of the cloud in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &'a V)> + '_ {
+        return self.nodes.iter().map(|(key, value)| (key, *value))
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> From<DataCloud<'a, K, V, S>> for SyncDataCloud<'a, K, V, S> {
+    fn from(value: DataCloud<'a, K, V, S>) -> Self {
+        return SyncDataCloud::from_hashmap(value.nodes.into_inner())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S> From<HashMap<K, &'a V, S>> for SyncDataCloud<'a, K, V, S> {
+    fn from(value: HashMap<K, &'a V, S>) -> Self {
+        return SyncDataCloud::from_hashmap(value)
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: BuildHasher + Default> FromIterator<(K, &'a V)> for SyncDataCloud<'a, K, V, S> {
+    fn from_iter<T: IntoIterator<Item = (K, &'a V)>>(iter: T) -> Self {
+        return SyncDataCloud::from_hashmap(iter.into_iter().collect())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash, V: ?Sized, S: Default> Default for SyncDataCloud<'a, K, V, S> {
+    fn default() -> Self {
+        return SyncDataCloud::with_hasher(S::default())
+    }
+}
+
+impl<'a, K: PartialEq + Eq + Hash + Debug, V: Debug + ?Sized, S> Debug for SyncDataCloud<'a, K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let nodes = self.nodes.read().unwrap_or_else(PoisonError::into_inner);
+        return f.debug_map().entries(nodes.iter()).finish()
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    borrow::Borrow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt::Debug,
+    hash::{BuildHasher, Hash},
+    ops::Deref,
+    rc::{self, Rc},
+    sync::{self, Arc}
+};
+
+use crate::DefaultHashBuilder;
+
+/// The fewest stored entries at which an insert purges dead ones.
+const MIN_PRUNE: usize = 16;
+
+/// A reference-counted pointer that a [`WeakCloud`] can hold weak references to.
+/// 
+/// Implemented for [`Rc`] and [`Arc`].
+pub trait SharedPointer<V: ?Sized>: Clone + Deref<Target = V> {
+    /// The weak counterpart of the pointer.
+    type Weak;
+
+    /// Returns a weak reference to the pointer's value.
+    fn downgrade(this: &Self) -> Self::Weak;
+
+    /// Returns a strong pointer to the value of `weak`, or `None` if it has been dropped.
+    fn upgrade(weak: &Self::Weak) -> Option<Self>;
+
+    /// Returns if the value of `weak` has been dropped.
+    fn is_dead(weak: &Self::Weak) -> bool;
+}
+
+impl<V: ?Sized> SharedPointer<V> for Rc<V> {
+    type Weak = rc::Weak<V>;
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        return Rc::downgrade(this);
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        return weak.upgrade();
+    }
+
+    fn is_dead(weak: &Self::Weak) -> bool {
+        return weak.strong_count() == 0;
+    }
+}
+
+impl<V: ?Sized> SharedPointer<V> for Arc<V> {
+    type Weak = sync::Weak<V>;
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        return Arc::downgrade(this);
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        return weak.upgrade();
+    }
+
+    fn is_dead(weak: &Self::Weak) -> bool {
+        return weak.strong_count() == 0;
+    }
+}
+
+/// A cloud of weak references to reference-counted values, for caches that must not keep their
+/// values alive.
+/// 
+/// Where a [`DataCloud`](crate::DataCloud) borrows values owned elsewhere for `'a`, a WeakCloud
+/// holds a [`Weak`](std::rc::Weak) to values owned by `Rc`s (or `Arc`s, with `P = Arc<V>`), so it
+/// has no lifetime at all. `get` hands out an upgraded strong pointer, and an entry is dropped
+/// once its value is gone: lookups remove the dead entries they find, and inserts purge every
+/// dead entry whenever the cloud has doubled in size since the last purge. [`WeakCloud::purge`]
+/// does it on demand.
+/// 
+/// # Examples
+/// ```
+/// use cloudr::WeakCloud;
+/// use std::rc::Rc;
+/// 
+/// let cache: WeakCloud<&str, String> = WeakCloud::new();
+/// let config = Rc::new("verbose".to_string());
+/// cache.insert("config", &config);
+/// 
+/// assert_eq!("verbose", *cache.get("config").unwrap());
+/// 
+/// drop(config);
+/// assert_eq!(None, cache.get("config"));
+/// assert!(cache.is_empty());
+/// ```
+pub struct WeakCloud<K: PartialEq + Eq + Hash, V: ?Sized, P: SharedPointer<V> = Rc<V>, S = DefaultHashBuilder> {
+    nodes: RefCell<HashMap<K, P::Weak, S>>,
+    prune_at: Cell<usize>,
+}
+
+impl<K: PartialEq + Eq + Hash, V: ?Sized, P: SharedPointer<V>> WeakCloud<K, V, P> {
+    /// Returns a new, empty WeakCloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::sync::Arc;
+    /// 
+    /// let cloud: WeakCloud<String, i32> = WeakCloud::new();
+    /// let shared: WeakCloud<String, i32, Arc<i32>> = WeakCloud::new();
+    /// ```
+    pub fn new() -> WeakCloud<K, V, P> {
+        return WeakCloud::with_hasher(Default::default());
+    }
+}
+
+impl<K: PartialEq + Eq + Hash, V: ?Sized, P: SharedPointer<V>, S> WeakCloud<K, V, P, S> {
+    /// Returns a new, empty WeakCloud which will use the given hash builder to hash keys.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::collections::hash_map::RandomState;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<String, i32, Rc<i32>, RandomState> = WeakCloud::with_hasher(RandomState::new());
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> WeakCloud<K, V, P, S> {
+        return WeakCloud {
+            nodes: RefCell::new(HashMap::with_hasher(hash_builder)),
+            prune_at: Cell::new(MIN_PRUNE),
+        }
+    }
+}
+
+impl<K: PartialEq + Eq + Hash, V: ?Sized, P: SharedPointer<V>, S: BuildHasher> WeakCloud<K, V, P, S> {
+    /// Inserts a weak reference to the value of `value` under `key`. Returns the previous value of
+    /// the key, if it was still alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// let (x, y) = (Rc::new(1), Rc::new(2));
+    /// 
+    /// assert_eq!(None, cloud.insert("k", &x));
+    /// assert_eq!(Some(x), cloud.insert("k", &y));
+    /// ```
+    pub fn insert(&self, key: K, value: &P) -> Option<P> {
+        let previous = self.nodes.borrow_mut().insert(key, P::downgrade(value));
+        if self.len() >= self.prune_at.get() {
+            self.purge();
+            self.prune_at.set(MIN_PRUNE.max(self.len() * 2));
+        }
+        return previous.and_then(|weak| P::upgrade(&weak));
+    }
+
+    /// Returns a strong pointer to the value of `key`, or `None` if there is none or it has been
+    /// dropped, in which case the entry is removed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<String, str> = WeakCloud::new();
+    /// let name: Rc<str> = Rc::from("cloudr");
+    /// cloud.insert("name".to_string(), &name);
+    /// 
+    /// let upgraded = cloud.get("name").unwrap();
+    /// assert!(Rc::ptr_eq(&name, &upgraded));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut nodes = self.nodes.borrow_mut();
+        let strong = P::upgrade(nodes.get(key)?);
+        if strong.is_none() {
+            nodes.remove(key);
+        }
+        return strong;
+    }
+
+    /// Removes a key from the cloud, returning its value if it was still alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// let x = Rc::new(1);
+    /// cloud.insert("x", &x);
+    /// 
+    /// assert_eq!(Some(x), cloud.remove("x"));
+    /// assert_eq!(None, cloud.remove("x"));
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let weak = self.nodes.borrow_mut().remove(key)?;
+        return P::upgrade(&weak);
+    }
+
+    /// Returns if the cloud holds a live value for this key. A dead entry found for the key is
+    /// removed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// let x = Rc::new(1);
+    /// cloud.insert("x", &x);
+    /// assert!(cloud.contains_key("x"));
+    /// 
+    /// drop(x);
+    /// assert!(!cloud.contains_key("x"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut nodes = self.nodes.borrow_mut();
+        return match nodes.get(key) {
+            Some(weak) if P::is_dead(weak) => {
+                nodes.remove(key);
+                false
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Removes every entry whose value has been dropped, and returns how many were removed.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<u32, u32> = WeakCloud::new();
+    /// let values: Vec<Rc<u32>> = (0..4).map(Rc::new).collect();
+    /// for value in &values {
+    ///     cloud.insert(**value, value);
+    /// }
+    /// 
+    /// drop(values);
+    /// assert_eq!(4, cloud.len());
+    /// assert_eq!(4, cloud.purge());
+    /// assert_eq!(0, cloud.len());
+    /// ```
+    pub fn purge(&self) -> usize {
+        let mut nodes = self.nodes.borrow_mut();
+        let before = nodes.len();
+        nodes.retain(|_, weak| !P::is_dead(weak));
+        return before - nodes.len();
+    }
+
+    /// Returns the number of entries whose value is still alive.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// let (x, y) = (Rc::new(1), Rc::new(2));
+    /// cloud.insert("x", &x);
+    /// cloud.insert("y", &y);
+    /// 
+    /// drop(y);
+    /// assert_eq!(1, cloud.live_len());
+    /// assert_eq!(2, cloud.len());
+    /// ```
+    pub fn live_len(&self) -> usize {
+        return self.nodes.borrow().values().filter(|weak| !P::is_dead(weak)).count();
+    }
+
+    /// Returns the number of stored entries, including the dead ones that haven't been removed
+    /// yet. Use [`WeakCloud::live_len`] to count only live ones.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// cloud.insert("x", &Rc::new(1));
+    /// 
+    /// assert_eq!(1, cloud.len());
+    /// assert_eq!(0, cloud.live_len());
+    /// ```
+    pub fn len(&self) -> usize {
+        return self.nodes.borrow().len();
+    }
+
+    /// Returns if the cloud stores no entry, like `len() == 0`. Dead entries that haven't been
+    /// removed yet count, so use `live_len() == 0` to check for live values.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// cloud.insert("x", &Rc::new(1));
+    /// assert!(!cloud.is_empty());
+    /// 
+    /// cloud.purge();
+    /// assert!(cloud.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Removes every entry from the cloud.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// let x = Rc::new(1);
+    /// cloud.insert("x", &x);
+    /// cloud.clear();
+    /// 
+    /// assert_eq!(0, cloud.len());
+    /// ```
+    pub fn clear(&self) {
+        self.nodes.borrow_mut().clear();
+    }
+
+    /// Keeps only the live entries for which the predicate returns `true`, removing the dead ones
+    /// along the way.
+    /// 
+    /// The predicate is called on strong pointers after the cloud has been released, so it may
+    /// use the cloud freely.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<u32, u32> = WeakCloud::new();
+    /// let values: Vec<Rc<u32>> = (0..6).map(Rc::new).collect();
+    /// for value in &values {
+    ///     cloud.insert(**value, value);
+    /// }
+    /// 
+    /// cloud.retain(|_, value| **value % 2 == 0);
+    /// 
+    /// assert_eq!(3, cloud.len());
+    /// assert!(cloud.contains_key(&4));
+    /// ```
+    pub fn retain<F>(&self, mut predicate: F)
+    where
+        K: Clone,
+        F: FnMut(&K, &P) -> bool,
+    {
+        self.purge();
+        let live = self.to_vec();
+        let rejected: Vec<K> = live.iter().filter(|(key, value)| !predicate(key, value)).map(|(key, _)| key.clone()).collect();
+        let mut nodes = self.nodes.borrow_mut();
+        for key in &rejected {
+            nodes.remove(key);
+        }
+    }
+
+    /// Returns the live entries of the cloud with strong pointers to their values, in arbitrary
+    /// order.
+    /// 
+    /// # Examples
+    /// ```
+    /// use cloudr::WeakCloud;
+    /// use std::rc::Rc;
+    /// 
+    /// let cloud: WeakCloud<&str, i32> = WeakCloud::new();
+    /// let x = Rc::new(1);
+    /// cloud.insert("x", &x);
+    /// cloud.insert("gone", &Rc::new(2));
+    /// 
+    /// assert_eq!(vec![("x", x)], cloud.to_vec());
+    /// ```
+    pub fn to_vec(&self) -> Vec<(K, P)>
+    where
+        K: Clone,
+    {
+        return self.nodes.borrow().iter()
+            .filter_map(|(key, weak)| Some((key.clone(), P::upgrade(weak)?)))
+            .collect();
+    }
+}
+
+impl<K: PartialEq + Eq + Hash, V: ?Sized, P: SharedPointer<V>, S: Default> Default for WeakCloud<K, V, P, S> {
+    fn default() -> Self {
+        return WeakCloud::with_hasher(Default::default());
+    }
+}
+
+impl<K: PartialEq + Eq + Hash + Debug, V: Debug + ?Sized, P: SharedPointer<V>, S> Debug for WeakCloud<K, V, P, S> {
+    /// Lists the live entries; dead ones are left out.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let nodes = self.nodes.borrow();
+        let live: Vec<(&K, P)> = nodes.iter().filter_map(|(key, weak)| Some((key, P::upgrade(weak)?))).collect();
+        return f.debug_map().entries(live.iter().map(|(key, value)| (key, &**value))).finish();
+    }
+}
@@ -0,0 +1,22 @@
+use cloudr::{CloudArena, DataCloud};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn references_survive_every_later_allocation(capacity in 0..8usize, values in prop::collection::vec(any::<String>(), 0..256)) {
+        let arena = CloudArena::with_capacity(capacity);
+        let cloud: DataCloud<'_, usize, String> = arena.cloud();
+        let mut stored = Vec::new();
+        for (key, value) in values.iter().enumerate() {
+            stored.push(arena.alloc_insert(&cloud, key, value.clone()));
+        }
+
+        prop_assert_eq!(values.len(), arena.len());
+        for (key, value) in values.iter().enumerate() {
+            prop_assert_eq!(value, stored[key]);
+            prop_assert!(std::ptr::eq(stored[key], cloud.get(&key).unwrap()));
+        }
+        drop(cloud);
+        prop_assert_eq!(values, arena.into_vec());
+    }
+}
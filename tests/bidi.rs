@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use cloudr::BiDataCloud;
+use cloudr::merge::KeepLeft;
+use proptest::prelude::*;
+
+const VALUES: [i32; 4] = [0, 1, 2, 3];
+
+#[derive(Debug, Clone)]
+enum Op {
+    Insert(u8, usize),
+    Remove(u8),
+    RemoveByValue(usize),
+    Retain(usize),
+    Merge(Vec<(u8, usize)>),
+    MergeKeepLeft(Vec<(u8, usize)>),
+    Clear,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let pairs = prop::collection::vec((0..8u8, 0..VALUES.len()), 0..6);
+    prop_oneof![
+        6 => (0..8u8, 0..VALUES.len()).prop_map(|(key, value)| Op::Insert(key, value)),
+        2 => (0..8u8).prop_map(Op::Remove),
+        2 => (0..VALUES.len()).prop_map(Op::RemoveByValue),
+        1 => (0..VALUES.len()).prop_map(Op::Retain),
+        1 => pairs.clone().prop_map(Op::Merge),
+        1 => pairs.prop_map(Op::MergeKeepLeft),
+        1 => Just(Op::Clear),
+    ]
+}
+
+fn other_of(pairs: &[(u8, usize)]) -> BiDataCloud<'static, u8, i32> {
+    let other = BiDataCloud::new();
+    for &(key, value) in pairs {
+        other.insert(key, &VALUES[value]);
+    }
+    other
+}
+
+proptest! {
+    #[test]
+    fn the_index_follows_every_mutation(ops in prop::collection::vec(op(), 0..64)) {
+        let cloud: BiDataCloud<'static, u8, i32> = BiDataCloud::new();
+        let mut model: HashMap<u8, i32> = HashMap::new();
+
+        for op in ops {
+            match op {
+                Op::Insert(key, value) => {
+                    cloud.insert(key, &VALUES[value]);
+                    model.insert(key, VALUES[value]);
+                }
+                Op::Remove(key) => {
+                    prop_assert_eq!(model.remove(&key), cloud.remove(&key).copied());
+                }
+                Op::RemoveByValue(value) => {
+                    let mut removed = cloud.remove_by_value(&VALUES[value]);
+                    removed.sort();
+                    let mut expected: Vec<u8> = model.iter().filter(|(_, v)| **v == VALUES[value]).map(|(k, _)| *k).collect();
+                    expected.sort();
+                    model.retain(|_, v| *v != VALUES[value]);
+                    prop_assert_eq!(expected, removed);
+                }
+                Op::Retain(value) => {
+                    cloud.retain(|_, v| **v != VALUES[value]);
+                    model.retain(|_, v| *v != VALUES[value]);
+                }
+                Op::Merge(pairs) => {
+                    cloud.merge_in_place(other_of(&pairs));
+                    for (key, value) in pairs {
+                        model.insert(key, VALUES[value]);
+                    }
+                }
+                Op::MergeKeepLeft(pairs) => {
+                    cloud.merge_in_place_by(other_of(&pairs), KeepLeft).unwrap();
+                    let last_wins: HashMap<u8, usize> = pairs.into_iter().collect();
+                    for (key, value) in last_wins {
+                        model.entry(key).or_insert(VALUES[value]);
+                    }
+                }
+                Op::Clear => {
+                    cloud.clear();
+                    model.clear();
+                }
+            }
+
+            prop_assert_eq!(model.len(), cloud.len());
+            for value in &VALUES {
+                let mut expected: Vec<u8> = model.iter().filter(|(_, v)| *v == value).map(|(k, _)| *k).collect();
+                expected.sort();
+                let mut indexed: Vec<u8> = cloud.get_by_value(value).map(|keys| keys.iter().copied().collect()).unwrap_or_default();
+                indexed.sort();
+
+                prop_assert_eq!(!expected.is_empty(), cloud.contains_value(value));
+                prop_assert_eq!(expected, indexed);
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Strategies and fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+use cloudr::DataCloud;
+use proptest::prelude::*;
+
+/// Keys each paired with the position of the pool value they reference, so several keys often
+/// alias the same value.
+pub fn keys() -> impl Strategy<Value = HashMap<String, usize>> {
+    prop::collection::hash_map("[a-z]{1,6}", 0..4usize, 0..24)
+}
+
+/// The values referenced by [`keys`].
+pub fn pool() -> impl Strategy<Value = Vec<Vec<i32>>> {
+    prop::collection::vec(prop::collection::vec(any::<i32>(), 0..4), 4)
+}
+
+/// Builds a cloud where each key references its position in `pool`.
+pub fn aliasing_cloud<'a, S>(keys: &HashMap<String, usize>, pool: &'a [Vec<i32>]) -> DataCloud<'a, String, Vec<i32>, S>
+where
+    S: BuildHasher + Default,
+{
+    let cloud = DataCloud::default();
+    for (key, &position) in keys {
+        cloud.insert(key.clone(), &pool[position]);
+    }
+    cloud
+}
+
+/// Builds a cloud from borrowed pairs, with a new hasher from `S::default()`.
+pub fn cloud_of<'a, K, V, S>(pairs: impl IntoIterator<Item = (&'a K, &'a V)>) -> DataCloud<'a, K, V, S>
+where
+    K: Copy + Eq + Hash + 'a,
+    V: 'a,
+    S: BuildHasher + Default,
+{
+    let cloud = DataCloud::default();
+    for (key, value) in pairs {
+        cloud.insert(*key, value);
+    }
+    cloud
+}
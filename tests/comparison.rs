@@ -0,0 +1,83 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::{BuildHasher, BuildHasherDefault, DefaultHasher},
+};
+
+use cloudr::DataCloud;
+use proptest::prelude::*;
+
+use common::cloud_of;
+
+mod common;
+
+/// Every cloud gets its own randomly seeded hasher, so two clouds holding the same pairs almost
+/// never iterate them in the same order.
+type Cloud<'a> = DataCloud<'a, u8, i32, RandomState>;
+
+fn hash_of(cloud: &Cloud<'_>) -> u64 {
+    BuildHasherDefault::<DefaultHasher>::default().hash_one(cloud)
+}
+
+proptest! {
+    #[test]
+    fn equality_ignores_insertion_and_iteration_order(pairs in prop::collection::hash_map(any::<u8>(), any::<i32>(), 0..64)) {
+        let forward: Cloud<'_> = cloud_of(pairs.iter());
+        let mut reversed: Vec<_> = pairs.iter().collect();
+        reversed.reverse();
+        let backward: Cloud<'_> = cloud_of(reversed);
+
+        prop_assert!(forward == backward);
+        prop_assert!(backward == forward);
+        prop_assert_eq!(Some(Ordering::Equal), forward.partial_cmp(&backward));
+    }
+
+    #[test]
+    fn a_strict_subset_is_never_equal(pairs in prop::collection::hash_map(any::<u8>(), any::<i32>(), 1..64)) {
+        let whole: Cloud<'_> = cloud_of(pairs.iter());
+        let prefix: Cloud<'_> = cloud_of(pairs.iter().skip(1));
+
+        prop_assert!(whole != prefix);
+        prop_assert!(prefix != whole);
+        prop_assert_ne!(Some(Ordering::Equal), whole.partial_cmp(&prefix));
+    }
+
+    #[test]
+    fn equality_compares_values_not_addresses(pairs in prop::collection::hash_map(any::<u8>(), any::<i32>(), 0..64)) {
+        let copies: HashMap<u8, i32> = pairs.clone();
+
+        prop_assert!(cloud_of::<_, _, RandomState>(pairs.iter()) == cloud_of(copies.iter()));
+    }
+
+    #[test]
+    fn equality_matches_map_equality(
+        left in prop::collection::hash_map(0..16u8, 0..4i32, 0..16),
+        right in prop::collection::hash_map(0..16u8, 0..4i32, 0..16),
+    ) {
+        prop_assert_eq!(left == right, cloud_of::<_, _, RandomState>(left.iter()) == cloud_of(right.iter()));
+    }
+
+    #[test]
+    fn equal_clouds_hash_equally(pairs in prop::collection::hash_map(any::<u8>(), any::<i32>(), 0..64)) {
+        let forward: Cloud<'_> = cloud_of(pairs.iter());
+        let mut reversed: Vec<_> = pairs.iter().collect();
+        reversed.reverse();
+        let backward: Cloud<'_> = cloud_of(reversed);
+
+        prop_assert_eq!(hash_of(&forward), hash_of(&backward));
+    }
+
+    #[test]
+    fn ordering_matches_sorted_pairs(
+        left in prop::collection::hash_map(0..16u8, 0..4i32, 0..16),
+        right in prop::collection::hash_map(0..16u8, 0..4i32, 0..16),
+    ) {
+        let sorted_left: BTreeMap<u8, i32> = left.iter().map(|(k, v)| (*k, *v)).collect();
+        let sorted_right: BTreeMap<u8, i32> = right.iter().map(|(k, v)| (*k, *v)).collect();
+        let (left_cloud, right_cloud): (Cloud<'_>, Cloud<'_>) = (cloud_of(left.iter()), cloud_of(right.iter()));
+
+        prop_assert_eq!(sorted_left.partial_cmp(&sorted_right), left_cloud.partial_cmp(&right_cloud));
+        prop_assert_eq!(sorted_left.cmp(&sorted_right), left_cloud.cmp(&right_cloud));
+        prop_assert_eq!(left_cloud == right_cloud, left_cloud.cmp(&right_cloud) == Ordering::Equal);
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use cloudr::DataCloud;
+use cloudr::diff::Comparison;
+use proptest::prelude::*;
+
+use common::cloud_of;
+
+mod common;
+
+type Cloud<'a> = DataCloud<'a, u8, i32>;
+
+proptest! {
+    #[test]
+    fn applying_a_diff_leads_to_its_target(
+        before in prop::collection::hash_map(0..32u8, 0..4i32, 0..32),
+        after in prop::collection::hash_map(0..32u8, 0..4i32, 0..32),
+    ) {
+        let (from, to): (Cloud<'_>, Cloud<'_>) = (cloud_of(before.iter()), cloud_of(after.iter()));
+
+        from.diff(&to).apply(&from);
+        prop_assert!(from == to);
+    }
+
+    #[test]
+    fn an_inverted_diff_leads_back(
+        before in prop::collection::hash_map(0..32u8, 0..4i32, 0..32),
+        after in prop::collection::hash_map(0..32u8, 0..4i32, 0..32),
+    ) {
+        let (from, to): (Cloud<'_>, Cloud<'_>) = (cloud_of(before.iter()), cloud_of(after.iter()));
+        let original: Cloud<'_> = cloud_of(before.iter());
+
+        let diff = from.diff_by(&to, Comparison::Address);
+        diff.apply(&from);
+        diff.invert().apply(&from);
+        prop_assert!(from == original);
+    }
+
+    #[test]
+    fn diff_partitions_the_touched_keys(
+        before in prop::collection::hash_map(0..32u8, 0..4i32, 0..32),
+        after in prop::collection::hash_map(0..32u8, 0..4i32, 0..32),
+    ) {
+        let (from, to): (Cloud<'_>, Cloud<'_>) = (cloud_of(before.iter()), cloud_of(after.iter()));
+        let diff = from.diff(&to);
+        let expected: HashMap<u8, ()> = before.keys().chain(after.keys())
+            .filter(|key| before.get(key) != after.get(key))
+            .map(|key| (*key, ()))
+            .collect();
+
+        prop_assert_eq!(expected.len(), diff.keys().count());
+        prop_assert!(diff.keys().all(|key| expected.contains_key(key)));
+    }
+}
+
+#[test]
+fn slices_sharing_a_start_are_different_referents() {
+    let text = String::from("hello");
+    let from: DataCloud<'_, &str, str> = DataCloud::new();
+    from.insert("a", &text[..1]);
+    from.insert("b", &text[..1]);
+    from.insert("c", &text[..5]);
+    let to: DataCloud<'_, &str, str> = DataCloud::new();
+    to.insert("a", &text[..5]);
+    to.insert("b", &text[..1]);
+    to.insert("c", &text[..5]);
+
+    let changed: Vec<&str> = from.diff_by(&to, Comparison::Address).changed.iter().map(|change| change.key).collect();
+    assert_eq!(vec!["a"], changed);
+    assert!(!from.ptr_eq(&to));
+    assert_eq!(1, from.keys_for_ptr(&text[..5]).count());
+
+    let mut groups = from.alias_groups();
+    groups.iter_mut().for_each(|keys| keys.sort());
+    assert_eq!(vec![vec!["a", "b"]], groups);
+}
@@ -0,0 +1,209 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use cloudr::DataCloud;
+use cloudr::error::CloudError;
+use cloudr::merge::{resolve_with, Resolver};
+
+use common::cloud_of;
+
+mod common;
+
+const VALUES: [i32; 5] = [0, 1, 2, 3, 4];
+static KEYS: [&str; 3] = ["a", "b", "c"];
+
+type Cloud = DataCloud<'static, &'static str, i32>;
+
+fn sorted(cloud: Cloud) -> Vec<(&'static str, i32)> {
+    let mut pairs: Vec<_> = cloud.into_vec().into_iter().map(|(key, value)| (key, *value)).collect();
+    pairs.sort();
+    pairs
+}
+
+#[test]
+fn nested_calls_see_the_queued_changes_and_apply_them_at_the_end() {
+    let cloud: Cloud = cloud_of(KEYS.iter().zip(&VALUES[1..]));
+
+    cloud.retain(|key, _| {
+        if *key == "a" {
+            let doubled: Vec<(&str, i32)> = cloud.map(|(key, value)| {
+                if *key == "b" {
+                    cloud.retain(|key, _| {
+                        cloud.insert("d", &VALUES[4]);
+                        assert!(cloud.contains_key("d"));
+                        *key != "c"
+                    });
+                    assert_eq!(Some(&1), cloud.remove("a"));
+                    assert!(!cloud.contains_key("a"));
+                    assert!(!cloud.contains_key("c"));
+                }
+                (*key, **value * 2)
+            }).collect();
+
+            assert_eq!(3, doubled.len());
+            assert_eq!(3, cloud.len());
+        }
+        *key != "b"
+    });
+
+    assert_eq!(vec![("d", 4)], sorted(cloud));
+}
+
+#[test]
+fn queued_changes_count_when_checking_occupancy() {
+    let cloud: Cloud = cloud_of(KEYS[..1].iter().zip(&VALUES[1..]));
+
+    cloud.retain(|_, _| {
+        assert_eq!(Ok(&VALUES[2]), cloud.try_insert("n", &VALUES[2]));
+        assert!(matches!(cloud.try_insert("n", &VALUES[3]), Err(CloudError::Occupied(_))));
+        assert_eq!(Some(&2), cloud.get("n"));
+
+        assert_eq!(None, cloud.insert("k", &VALUES[4]));
+        assert_eq!(Some(&4), cloud.remove("k"));
+        assert_eq!(None, cloud.remove("k"));
+        assert_eq!(Err(CloudError::KeyNotFound), cloud.try_remove("k"));
+
+        assert_eq!(Ok(&1), cloud.try_remove("a"));
+        assert_eq!(None, cloud.insert("a", &VALUES[3]));
+        true
+    });
+
+    assert_eq!(vec![("a", 3), ("n", 2)], sorted(cloud));
+}
+
+#[test]
+fn an_operation_applies_its_own_changes_before_those_of_its_callbacks() {
+    let cloud: Cloud = cloud_of(KEYS[..2].iter().zip(&VALUES[1..]));
+
+    cloud.retain(|key, _| {
+        if *key == "a" {
+            cloud.insert("a", &VALUES[4]);
+            cloud.remove("b");
+        }
+        *key != "a"
+    });
+
+    assert_eq!(vec![("a", 4)], sorted(cloud));
+}
+
+#[test]
+fn the_last_change_to_a_key_wins_and_clear_drops_earlier_ones() {
+    let cloud: Cloud = cloud_of(KEYS.iter().zip(&VALUES[1..]));
+
+    cloud.retain(|_, _| {
+        cloud.insert("x", &VALUES[0]);
+        cloud.clear();
+        cloud.insert("y", &VALUES[0]);
+        cloud.remove("a");
+        cloud.insert("y", &VALUES[4]);
+        true
+    });
+
+    assert_eq!(vec![("y", 4)], sorted(cloud));
+}
+
+#[test]
+fn merges_and_dedups_defer_the_changes_of_their_callbacks() {
+    let x = 7;
+    let cloud: DataCloud<'_, &str, i32> = DataCloud::new();
+    cloud.insert("a", &x);
+    cloud.insert("b", &x);
+    cloud.insert("c", &VALUES[1]);
+
+    let removed = cloud.dedup_aliases(|keys: &[&str]| {
+        cloud.insert("d", &VALUES[2]);
+        assert_eq!(3, cloud.len());
+        keys.iter().position(|key| *key == "a")
+    });
+    assert_eq!(vec!["b"], removed);
+    assert_eq!(3, cloud.len());
+
+    let other: DataCloud<'_, &str, i32> = DataCloud::new();
+    other.insert("c", &VALUES[3]);
+    let report = cloud.merge_in_place_by(other, resolve_with(|key: &&str, left: &i32, _right: &i32| {
+        cloud.retain(|other, _| other != key);
+        assert!(!cloud.contains_key("c"));
+        left
+    })).unwrap();
+    assert_eq!(vec!["c"], report.conflicts);
+
+    let mut pairs: Vec<_> = cloud.into_vec().into_iter().map(|(key, value)| (key, *value)).collect();
+    pairs.sort();
+    assert_eq!(vec![("a", 7), ("d", 2)], pairs);
+}
+
+#[test]
+fn a_refused_merge_still_applies_the_changes_of_its_resolver() {
+    struct Refuse<'c>(&'c Cloud);
+
+    impl Resolver<&'static str, &'static i32> for Refuse<'_> {
+        fn resolve(&mut self, _key: &&'static str, _left: &'static i32, _right: &'static i32) -> Option<&'static i32> {
+            self.0.insert("z", &VALUES[4]);
+            None
+        }
+    }
+
+    let cloud: Cloud = cloud_of(KEYS[..1].iter().zip(&VALUES[1..]));
+    let other: Cloud = cloud_of(KEYS[..2].iter().zip(&VALUES[2..]));
+
+    let refused = cloud.merge_in_place_by(other, Refuse(&cloud)).unwrap_err();
+    assert_eq!("a", refused.key);
+    assert_eq!(vec![("a", 1), ("z", 4)], sorted(cloud));
+}
+
+#[test]
+fn a_leaked_map_keeps_the_cloud_borrowed_instead_of_deferring() {
+    let cloud: Cloud = cloud_of(KEYS[..2].iter().zip(&VALUES[1..]));
+
+    let mut iterator = cloud.map(|(key, value)| {
+        cloud.insert("c", &VALUES[3]);
+        (*key, **value)
+    });
+    iterator.next();
+    std::mem::forget(iterator);
+
+    assert!(cloud.contains_key("c"));
+    assert_eq!(Err(CloudError::BorrowConflict), cloud.try_insert("d", &VALUES[4]));
+    assert_eq!(Err(CloudError::BorrowConflict), cloud.try_remove("a"));
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| cloud.insert("d", &VALUES[4]))).is_err());
+    assert!(!cloud.contains_key("d"));
+}
+
+#[test]
+fn a_panicking_callback_discards_the_queue() {
+    let cloud: Cloud = cloud_of(KEYS[..2].iter().zip(&VALUES[1..]));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        cloud.retain(|_, _| {
+            cloud.insert("c", &VALUES[3]);
+            panic!("predicate failed");
+        });
+    }));
+    assert!(result.is_err());
+    assert!(!cloud.contains_key("c"));
+
+    cloud.insert("c", &VALUES[3]);
+    assert!(cloud.contains_key("c"));
+    assert_eq!(vec![("a", 1), ("b", 2), ("c", 3)], sorted(cloud));
+}
+
+#[test]
+fn operations_run_while_unwinding_still_apply_their_changes() {
+    struct Cleanup<'c>(&'c Cloud);
+
+    impl Drop for Cleanup<'_> {
+        fn drop(&mut self) {
+            self.0.retain(|key, _| {
+                self.0.insert("z", &VALUES[4]);
+                *key != "a"
+            });
+        }
+    }
+
+    let cloud: Cloud = cloud_of(KEYS[..2].iter().zip(&VALUES[1..]));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _cleanup = Cleanup(&cloud);
+        panic!("unwinding");
+    }));
+    assert!(result.is_err());
+    assert_eq!(vec![("b", 2), ("z", 4)], sorted(cloud));
+}
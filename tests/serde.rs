@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use cloudr::{CloudArena, DataCloud};
+use cloudr::serde::{CloudSeed, Sharing};
+use proptest::prelude::*;
+use serde::de::DeserializeSeed;
+
+use common::{aliasing_cloud, keys, pool};
+
+mod common;
+
+type Cloud<'a> = DataCloud<'a, String, Vec<i32>>;
+
+fn aliased(cloud: &Cloud<'_>, left: &str, right: &str) -> bool {
+    std::ptr::eq(cloud.get(left).unwrap(), cloud.get(right).unwrap())
+}
+
+fn check_copy<'a>(cloud: &Cloud<'a>, copy: &Cloud<'a>, arena: &CloudArena<Vec<i32>>, sharing: Sharing) -> Result<(), TestCaseError> {
+    prop_assert_eq!(cloud, copy);
+    match sharing {
+        Sharing::Duplicate => prop_assert_eq!(cloud.len(), arena.len()),
+        Sharing::Preserve => {
+            let referents: usize = cloud.alias_groups().iter().map(|keys| keys.len() - 1).sum();
+            prop_assert_eq!(cloud.len() - referents, arena.len());
+            for left in copy.keys() {
+                for right in copy.keys() {
+                    prop_assert_eq!(aliased(cloud, &left, &right), aliased(copy, &left, &right));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn json_round_trips(keys in keys(), pool in pool(), preserve in any::<bool>()) {
+        let sharing = if preserve { Sharing::Preserve } else { Sharing::Duplicate };
+        let cloud: Cloud<'_> = aliasing_cloud(&keys, &pool);
+        let json = serde_json::to_string(&cloud.serialize_with(sharing)).unwrap();
+
+        let arena = CloudArena::new();
+        let copy: Cloud<'_> = arena.deserialize_cloud(&mut serde_json::Deserializer::from_str(&json), sharing).unwrap();
+        check_copy(&cloud, &copy, &arena, sharing)?;
+    }
+
+    #[test]
+    fn postcard_round_trips(keys in keys(), pool in pool(), preserve in any::<bool>()) {
+        let sharing = if preserve { Sharing::Preserve } else { Sharing::Duplicate };
+        let cloud: Cloud<'_> = aliasing_cloud(&keys, &pool);
+        let bytes = postcard::to_allocvec(&cloud.serialize_with(sharing)).unwrap();
+
+        let arena = CloudArena::new();
+        let seed = CloudSeed::with_sharing(&arena, sharing);
+        let copy: Cloud<'_> = seed.deserialize(&mut postcard::Deserializer::from_bytes(&bytes)).unwrap();
+        check_copy(&cloud, &copy, &arena, sharing)?;
+    }
+}
+
+#[test]
+fn plain_serialization_is_a_map() {
+    let pool = [vec![1, 2]];
+    let cloud: Cloud<'_> = aliasing_cloud(&HashMap::from([("a".to_string(), 0)]), &pool);
+
+    assert_eq!(r#"{"a":[1,2]}"#, serde_json::to_string(&cloud).unwrap());
+}
+
+#[test]
+fn out_of_range_positions_are_rejected() {
+    let arena: CloudArena<Vec<i32>> = CloudArena::new();
+    let json = r#"{"keys":[["a",1]],"values":[[1]]}"#;
+    let result: Result<Cloud<'_>, _> = arena.deserialize_cloud(&mut serde_json::Deserializer::from_str(json), Sharing::Preserve);
+
+    assert!(result.unwrap_err().to_string().contains("the position of a serialized value"));
+}
@@ -0,0 +1,185 @@
+use std::{
+    hash::{BuildHasher, DefaultHasher, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Barrier,
+    },
+    thread,
+};
+
+use cloudr::ShardedCloud;
+
+const THREADS: usize = 8;
+const ROUNDS: usize = 2_000;
+
+#[test]
+fn disjoint_writers_do_not_lose_inserts() {
+    let cloud: ShardedCloud<'_, usize, usize> = ShardedCloud::with_shards(16);
+    let values: Vec<usize> = (0..THREADS * ROUNDS).collect();
+
+    thread::scope(|scope| {
+        for chunk in values.chunks(ROUNDS) {
+            let cloud = &cloud;
+            scope.spawn(move || {
+                for value in chunk {
+                    assert_eq!(None, cloud.insert(*value, value));
+                }
+            });
+        }
+    });
+
+    assert_eq!(values.len(), cloud.len());
+    for value in &values {
+        assert!(std::ptr::eq(value, cloud.get(value).unwrap()));
+    }
+}
+
+#[test]
+fn contended_inserts_hand_back_every_replaced_value_once() {
+    // Every insert on the same key swaps one reference for another, so across all threads each
+    // inserted reference must be handed back as "replaced" exactly once, except the one left stored.
+    let cloud: ShardedCloud<'_, &str, usize> = ShardedCloud::with_shards(4);
+    let values: Vec<usize> = (0..THREADS * ROUNDS).collect();
+    let barrier = Barrier::new(THREADS);
+
+    let replaced: Vec<Vec<usize>> = thread::scope(|scope| {
+        let handles: Vec<_> = values
+            .chunks(ROUNDS)
+            .map(|chunk| {
+                let (cloud, barrier) = (&cloud, &barrier);
+                scope.spawn(move || {
+                    barrier.wait();
+                    chunk.iter().filter_map(|value| cloud.insert("hot", value).copied()).collect()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let mut seen: Vec<usize> = replaced.into_iter().flatten().collect();
+    seen.push(*cloud.get("hot").unwrap());
+    seen.sort_unstable();
+    assert_eq!(values, seen);
+}
+
+#[test]
+fn contended_removes_succeed_exactly_once() {
+    let cloud: ShardedCloud<'_, usize, usize> = ShardedCloud::with_shards(8);
+    let values: Vec<usize> = (0..ROUNDS).collect();
+    for value in &values {
+        cloud.insert(*value, value);
+    }
+    let removed = AtomicUsize::new(0);
+    let barrier = Barrier::new(THREADS);
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            let (cloud, removed, barrier, values) = (&cloud, &removed, &barrier, &values);
+            scope.spawn(move || {
+                barrier.wait();
+                for value in values {
+                    if cloud.remove(value).is_some() {
+                        removed.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(ROUNDS, removed.into_inner());
+    assert!(cloud.is_empty());
+}
+
+#[test]
+fn readers_only_observe_inserted_references() {
+    let cloud: ShardedCloud<'_, usize, usize> = ShardedCloud::with_shards(8);
+    let values: Vec<usize> = (0..ROUNDS).collect();
+
+    thread::scope(|scope| {
+        let (cloud, values) = (&cloud, &values);
+        scope.spawn(move || {
+            for value in values {
+                cloud.insert(*value, value);
+            }
+        });
+        for _ in 0..THREADS - 1 {
+            scope.spawn(move || {
+                for (key, expected) in values.iter().enumerate() {
+                    if let Some(value) = cloud.get(&key) {
+                        assert!(std::ptr::eq(expected, value));
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(ROUNDS, cloud.len());
+}
+
+#[test]
+fn retain_runs_alongside_writers() {
+    let cloud: ShardedCloud<'_, usize, usize> = ShardedCloud::with_shards(8);
+    let values: Vec<usize> = (0..THREADS * ROUNDS).collect();
+
+    thread::scope(|scope| {
+        for chunk in values.chunks(ROUNDS) {
+            let cloud = &cloud;
+            scope.spawn(move || {
+                for value in chunk {
+                    cloud.insert(*value, value);
+                }
+            });
+        }
+        let cloud = &cloud;
+        scope.spawn(move || {
+            for _ in 0..16 {
+                cloud.retain(|_, value| **value % 2 == 0);
+            }
+        });
+    });
+
+    cloud.retain(|_, value| **value % 2 == 0);
+    assert_eq!(values.len() / 2, cloud.len());
+    for shard in cloud.shards() {
+        assert!(shard.iter().all(|(key, value)| key == value && value % 2 == 0));
+    }
+}
+
+/// Yields 32-bit hashes, like FxHasher does on 32-bit targets.
+#[derive(Default)]
+struct Narrow(DefaultHasher);
+
+impl Hasher for Narrow {
+    fn finish(&self) -> u64 {
+        self.0.finish() & 0xFFFF_FFFF
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+}
+
+#[derive(Clone)]
+struct NarrowState;
+
+impl BuildHasher for NarrowState {
+    type Hasher = Narrow;
+
+    fn build_hasher(&self) -> Narrow {
+        Narrow::default()
+    }
+}
+
+#[test]
+fn narrow_hashes_spread_over_every_shard() {
+    let cloud: ShardedCloud<'_, usize, usize, NarrowState> = ShardedCloud::with_shards_and_hasher(16, NarrowState);
+    let values: Vec<usize> = (0..1_600).collect();
+    for value in &values {
+        cloud.insert(*value, value);
+    }
+
+    for shard in cloud.shards() {
+        let len = shard.iter().count();
+        assert!((50..=150).contains(&len), "{} keys in one shard", len);
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasherDefault, DefaultHasher},
+};
+
+use cloudr::{CloudArena, DataCloud};
+use cloudr::error::SnapshotError;
+use cloudr::snapshot::VERSION;
+use proptest::prelude::*;
+
+use common::{aliasing_cloud, keys, pool};
+
+mod common;
+
+type Cloud<'a> = DataCloud<'a, String, Vec<i32>, RandomState>;
+
+fn snapshot_of(cloud: &Cloud<'_>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    cloud.write_snapshot(&mut bytes).unwrap();
+    bytes
+}
+
+fn read(bytes: &[u8]) -> Result<usize, SnapshotError> {
+    let arena = CloudArena::new();
+    let cloud: Cloud<'_> = arena.read_snapshot(bytes)?;
+    Ok(cloud.len())
+}
+
+proptest! {
+    #[test]
+    fn snapshots_round_trip_with_their_aliasing(keys in keys(), pool in pool()) {
+        let cloud: Cloud<'_> = aliasing_cloud(&keys, &pool);
+        let bytes = snapshot_of(&cloud);
+
+        let arena = CloudArena::new();
+        let copy: Cloud<'_> = arena.read_snapshot(bytes.as_slice()).unwrap();
+        prop_assert_eq!(&cloud, &copy);
+
+        let referents: usize = cloud.alias_groups().iter().map(|keys| keys.len() - 1).sum();
+        prop_assert_eq!(cloud.len() - referents, arena.len());
+        for left in keys.keys() {
+            for right in keys.keys() {
+                prop_assert_eq!(keys[left] == keys[right], std::ptr::eq(copy.get(left).unwrap(), copy.get(right).unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn every_truncation_is_reported(keys in keys(), pool in pool()) {
+        let bytes = snapshot_of(&aliasing_cloud(&keys, &pool));
+        for len in 0..bytes.len() {
+            prop_assert!(matches!(read(&bytes[..len]), Err(SnapshotError::Truncated)), "prefix of {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn every_flipped_body_byte_is_reported(keys in keys(), pool in pool(), position in any::<prop::sample::Index>(), flip in 1..=255u8) {
+        let mut bytes = snapshot_of(&aliasing_cloud(&keys, &pool));
+        let position = 26 + position.index(bytes.len() - 26);
+        bytes[position] ^= flip;
+
+        let result = read(&bytes);
+        prop_assert!(matches!(result, Err(SnapshotError::ChecksumMismatch { .. })), "{:?}", result);
+    }
+}
+
+#[test]
+fn headers_are_checked() {
+    let pool = [vec![1]];
+    let bytes = snapshot_of(&aliasing_cloud(&HashMap::from([("a".to_string(), 0)]), &pool));
+    assert_eq!(1, read(&bytes).unwrap());
+
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert!(matches!(read(&magic), Err(SnapshotError::BadMagic)));
+
+    let mut version = bytes.clone();
+    version[8..10].copy_from_slice(&(VERSION + 1).to_le_bytes());
+    assert!(matches!(read(&version), Err(SnapshotError::UnsupportedVersion(found)) if found == VERSION + 1));
+
+    let arena = CloudArena::new();
+    let other: Result<DataCloud<'_, String, Vec<i32>, BuildHasherDefault<DefaultHasher>>, _> = arena.read_snapshot(bytes.as_slice());
+    assert!(matches!(other, Err(SnapshotError::HasherMismatch { expected: 2, found: 1 })));
+}
+
+#[test]
+fn corrupt_bodies_with_valid_checksums_are_reported() {
+    let x = 1;
+    let cloud: DataCloud<'_, u8, i32, RandomState> = DataCloud::default();
+    cloud.insert(1, &x);
+    let mut bytes = Vec::new();
+    cloud.write_snapshot(&mut bytes).unwrap();
+
+    // Point the key to a value that doesn't exist, then fix up the checksum.
+    let index = bytes.len() - 8;
+    bytes[index] = 1;
+    let checksum = crc32(&bytes[26..]);
+    bytes[22..26].copy_from_slice(&checksum.to_le_bytes());
+
+    let arena = CloudArena::new();
+    let result: Result<DataCloud<'_, u8, i32, RandomState>, _> = arena.read_snapshot(bytes.as_slice());
+    assert!(matches!(result, Err(SnapshotError::Corrupt(_))));
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, rc::Rc, sync::Arc, thread};
+
+use cloudr::WeakCloud;
+use proptest::prelude::*;
+
+const SLOTS: usize = 4;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Insert(u8, usize),
+    Replace(usize),
+    Get(u8),
+    Remove(u8),
+    Purge,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => (0..16u8, 0..SLOTS).prop_map(|(key, slot)| Op::Insert(key, slot)),
+        2 => (0..SLOTS).prop_map(Op::Replace),
+        2 => (0..16u8).prop_map(Op::Get),
+        1 => (0..16u8).prop_map(Op::Remove),
+        1 => Just(Op::Purge),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn only_live_values_are_handed_out(ops in prop::collection::vec(op(), 0..128)) {
+        let cloud: WeakCloud<u8, (usize, u32)> = WeakCloud::new();
+        // Each slot owns one value at a time; replacing it drops the previous one.
+        let mut slots: Vec<Rc<(usize, u32)>> = (0..SLOTS).map(|slot| Rc::new((slot, 0))).collect();
+        let mut model: HashMap<u8, (usize, u32)> = HashMap::new();
+        let alive = |slots: &[Rc<(usize, u32)>], value: &(usize, u32)| *slots[value.0] == *value;
+
+        for op in ops {
+            match op {
+                Op::Insert(key, slot) => {
+                    let previous = cloud.insert(key, &slots[slot]).map(|value| *value);
+                    let expected = model.insert(key, *slots[slot]).filter(|value| alive(&slots, value));
+                    prop_assert_eq!(expected, previous);
+                }
+                Op::Replace(slot) => {
+                    let generation = slots[slot].1 + 1;
+                    slots[slot] = Rc::new((slot, generation));
+                }
+                Op::Get(key) => {
+                    let expected = model.get(&key).copied().filter(|value| alive(&slots, value));
+                    prop_assert_eq!(expected, cloud.get(&key).map(|value| *value));
+                }
+                Op::Remove(key) => {
+                    let expected = model.remove(&key).filter(|value| alive(&slots, value));
+                    prop_assert_eq!(expected, cloud.remove(&key).map(|value| *value));
+                }
+                Op::Purge => {
+                    let dead = model.values().filter(|value| !alive(&slots, value)).count();
+                    let removed = cloud.purge();
+                    prop_assert!(removed <= dead);
+                    prop_assert_eq!(cloud.live_len(), cloud.len());
+                }
+            }
+            prop_assert_eq!(model.values().filter(|value| alive(&slots, value)).count(), cloud.live_len());
+        }
+    }
+}
+
+#[test]
+fn dead_entries_are_pruned_as_the_cloud_grows() {
+    let cloud: WeakCloud<usize, usize> = WeakCloud::new();
+    let kept = Rc::new(0);
+    cloud.insert(0, &kept);
+
+    for key in 1..10_000 {
+        cloud.insert(key, &Rc::new(key));
+        assert!(cloud.len() <= 32, "{} entries after {} inserts", cloud.len(), key);
+    }
+
+    assert_eq!(1, cloud.live_len());
+    assert_eq!(Some(kept), cloud.get(&0));
+}
+
+#[test]
+fn arc_clouds_can_move_between_threads() {
+    let cloud: WeakCloud<&str, String, Arc<String>> = WeakCloud::new();
+    let value = Arc::new("shared".to_string());
+    cloud.insert("value", &value);
+
+    let cloud = thread::spawn(move || {
+        assert_eq!("shared", *cloud.get("value").unwrap());
+        cloud
+    }).join().unwrap();
+
+    drop(value);
+    assert_eq!(None, cloud.get("value"));
+}